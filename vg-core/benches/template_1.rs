@@ -0,0 +1,21 @@
+use {
+    vg_core::{ FileCache, Template, },
+    criterion::{ criterion_group, criterion_main, Criterion, },
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut cache = FileCache::enabled();
+    let template = Template::from_file(
+        "./test/template/1/template.jinja",
+        &mut cache,
+    ).unwrap();
+
+    c.bench_function("Template 1", |b| b.iter(|| template.render_implemented(
+        "./test/template/1",
+        [ ("title", "First"), ("author", "Ron") ],
+        &mut cache,
+    )));
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
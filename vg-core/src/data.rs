@@ -0,0 +1,250 @@
+// vg-core::data: The structured data files read by vg templates.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The structured data files read by vg templates.
+
+use {
+    crate::{ Error, Result, Value, },
+    std::{
+        collections::BTreeMap,
+        path::Path,
+    },
+};
+
+/// The format of a data file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Format {
+    Json,
+    Ron,
+    Toml,
+    Csv,
+}
+
+impl Format {
+    /// The format of a data file by the extension of its path, or `None` when
+    /// the path is not a data file.
+    pub(crate) fn of(p: &Path) -> Option<Self> {
+        match p.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "ron" => Some(Self::Ron),
+            "toml" => Some(Self::Toml),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Parse the content of a data file of this format.
+    pub(crate) fn parse(self, p: &Path, content: &str) -> Result<Value> {
+        let error = |reason: String| Error::Data { path: p.into(), reason, };
+
+        match self {
+            Self::Json => serde_json::from_str(content)
+                .map(from_json)
+                .map_err(|e| error(e.to_string())),
+            Self::Ron => ron::from_str(content)
+                .map(from_ron)
+                .map_err(|e| error(e.to_string())),
+            Self::Toml => content.parse::<toml::Table>()
+                .map(|t| from_toml(toml::Value::Table(t)))
+                .map_err(|e| error(e.message().to_owned())),
+            Self::Csv => from_csv(content).map_err(error),
+        }
+    }
+
+    /// The value iterated by a `for` tag from a data file of this format. A
+    /// TOML document may only be a table, so one holding nothing but an array
+    /// iterates that array.
+    pub(crate) fn iterable(self, value: Value) -> Value {
+        match (self, value) {
+            (Self::Toml, Value::Map(map)) if map.len() == 1 && matches!(map.values().next(), Some(Value::List(_))) => {
+                map.into_values().next().unwrap_or_default()
+            },
+            (_, value) => value,
+        }
+    }
+}
+
+/// Parse the front matter of a template, either a RON map or struct, or lines
+/// of `key: value` whose values are strings unless they are valid RON, or
+/// `None` when the content is neither.
+pub(crate) fn front_matter(content: &str) -> Option<BTreeMap<String, Value>> {
+    let trimmed = content.trim_start();
+
+    if trimmed.starts_with('(') || trimmed.starts_with('{') {
+        return match ron::from_str(content).map(from_ron) {
+            Ok(Value::Map(map)) => Some(map),
+            _ => None,
+        };
+    }
+
+    let mut map = BTreeMap::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim_end();
+
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+
+        map.insert(key.to_owned(), front_matter_value(value.trim()));
+    }
+
+    Some(map)
+}
+
+/// A value of front matter is RON, such as a quoted string, a number or a
+/// list, else the text as written. Numbers are only read when they are written
+/// as they would be rendered, so versions and codes such as `1.10` or `01234`
+/// are kept as text.
+fn front_matter_value(value: &str) -> Value {
+    let is_ron = matches!(value, "true" | "false")
+        || value.starts_with(['"', '[', '(', '{', '-'])
+        || value.starts_with(|c: char| c.is_ascii_digit());
+
+    if !is_ron {
+        return Value::from(value);
+    }
+
+    match ron::from_str(value).map(from_ron) {
+        Ok(Value::Number(n)) if n.to_string() != value => Value::from(value),
+        Ok(parsed) => parsed,
+        Err(_) => Value::from(value),
+    }
+}
+
+pub(crate) fn from_json(json: serde_json::Value) -> Value {
+    use serde_json::Value as Json;
+
+    match json {
+        Json::Null => Value::Null,
+        Json::Bool(b) => Value::Bool(b),
+        Json::Number(n) => n.as_f64().map_or(Value::Null, Value::Number),
+        Json::String(s) => Value::String(s),
+        Json::Array(a) => Value::List(a.into_iter().map(from_json).collect()),
+        Json::Object(o) => Value::Map(o.into_iter().map(|(k, v)| (k, from_json(v))).collect()),
+    }
+}
+
+fn from_ron(ron: ron::Value) -> Value {
+    use ron::Value as Ron;
+
+    match ron {
+        Ron::Unit | Ron::Option(None) => Value::Null,
+        Ron::Option(Some(v)) => from_ron(*v),
+        Ron::Bool(b) => Value::Bool(b),
+        Ron::Char(c) => Value::String(c.to_string()),
+        Ron::Number(n) => Value::Number(n.into_f64()),
+        Ron::String(s) => Value::String(s),
+        Ron::Seq(s) => Value::List(s.into_iter().map(from_ron).collect()),
+        Ron::Map(m) => Value::Map(
+            m.into_iter()
+                .map(|(k, v)| (from_ron(k).to_string(), from_ron(v)))
+                .collect()
+        ),
+    }
+}
+
+fn from_toml(toml: toml::Value) -> Value {
+    use toml::Value as Toml;
+
+    match toml {
+        Toml::String(s) => Value::String(s),
+        Toml::Integer(i) => Value::from(i),
+        Toml::Float(f) => Value::Number(f),
+        Toml::Boolean(b) => Value::Bool(b),
+        Toml::Datetime(d) => Value::String(d.to_string()),
+        Toml::Array(a) => Value::List(a.into_iter().map(from_toml).collect()),
+        Toml::Table(t) => Value::Map(t.into_iter().map(|(k, v)| (k, from_toml(v))).collect()),
+    }
+}
+
+/// Split CSV content into records of fields. Quoted fields may contain
+/// commas, newlines and doubled quotes.
+fn csv_records(content: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            },
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {},
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            },
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("quoted field is never closed".to_owned());
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    // blank lines are not records
+    records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+
+    Ok(records)
+}
+
+/// Parse CSV content into a list of maps keyed by the fields of the header.
+fn from_csv(content: &str) -> std::result::Result<Value, String> {
+    let mut records = csv_records(content)?.into_iter();
+
+    let Some(header) = records.next() else {
+        return Ok(Value::List(Vec::new()));
+    };
+
+    let mut rows = Vec::new();
+
+    for (idx, record) in records.enumerate() {
+        if record.len() != header.len() {
+            return Err(format!(
+                "row {} has {} field(s), expected {}",
+                idx + 1, record.len(), header.len()
+            ));
+        }
+
+        let row = header.iter().cloned()
+            .zip(record.into_iter().map(Value::String))
+            .collect::<BTreeMap<String, Value>>();
+
+        rows.push(Value::Map(row));
+    }
+
+    Ok(Value::List(rows))
+}
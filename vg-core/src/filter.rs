@@ -0,0 +1,694 @@
+// vg-core::filter: The filters applied to the values of vg variables.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The filters applied to the values of vg variables.
+
+use {
+    crate::{ Error, Result, },
+    std::{
+        collections::HashMap,
+        fmt::{ Debug, Write, },
+        sync::Arc,
+        time::SystemTime,
+    },
+    chrono::{
+        DateTime,
+        NaiveDate,
+        NaiveDateTime,
+        Utc,
+        format::{ Item, StrftimeItems, },
+    },
+    md5::Md5,
+    nfm_core::Parser as NfmParser,
+    sha2::{ Digest, Sha256, },
+};
+
+/// A filter applied to the value of a variable, such as `trim` in
+/// `{{ name | trim }}`.
+///
+/// # Examples
+///
+/// ```rust
+/// use vg_core::{ CompileOptions, Error, FileCache, Filter, Parser, Result, };
+///
+/// #[derive(Debug)]
+/// struct Initials;
+///
+/// impl Filter for Initials {
+///     fn name(&self) -> &str {
+///         "initials"
+///     }
+///
+///     fn apply(&self, input: &str, _: &[String]) -> Result<String> {
+///         Ok(input.split_whitespace().filter_map(|w| w.chars().next()).collect())
+///     }
+/// }
+///
+/// let mut options = CompileOptions::default();
+/// options.filters.insert(Initials);
+///
+/// let mut cache = FileCache::enabled();
+/// cache.insert("/page.jinja", "{{ name | initials }}".to_owned());
+/// let output = Parser::compile_with_options(
+///     "/", "/page.jinja", [("name", "Frankie Baffa")], &mut cache, &options
+/// ).unwrap();
+/// assert_eq!("FB", output);
+/// ```
+pub trait Filter: Debug + Send + Sync {
+    /// The name the filter is called by.
+    fn name(&self) -> &str;
+
+    /// Apply the filter to a value with the arguments following its name.
+    fn apply(&self, input: &str, arguments: &[String]) -> Result<String>;
+
+    /// Apply the filter within the context of a compilation, such as the time
+    /// fixed for it. Unless implemented, the context is ignored.
+    fn apply_within(&self, input: &str, arguments: &[String], _context: &FilterContext) -> Result<String> {
+        self.apply(input, arguments)
+    }
+
+    /// Whether the output of the filter is safe from autoescaping.
+    fn is_safe(&self) -> bool {
+        false
+    }
+}
+
+/// The context of the compilation a filter is applied within.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FilterContext {
+    /// The time fixed for the whole compilation, as the value of `now`, in
+    /// seconds since the Unix epoch.
+    pub now: i64,
+}
+
+impl Default for FilterContext {
+    fn default() -> Self {
+        Self { now: now(), }
+    }
+}
+
+/// Fail unless a filter was given exactly `n` arguments.
+pub(crate) fn expect_arguments(filter: &str, arguments: &[String], n: usize) -> Result<()> {
+    expect_arguments_between(filter, arguments, n, n)
+}
+
+/// Fail unless a filter was given from `min` to `max` arguments.
+pub(crate) fn expect_arguments_between(filter: &str, arguments: &[String], min: usize, max: usize) -> Result<()> {
+    if arguments.len() < min || arguments.len() > max {
+        let expected = if min == max {
+            format!("{min}")
+        } else {
+            format!("{min} to {max}")
+        };
+
+        return Err(Error::Filter {
+            name: filter.to_owned(),
+            reason: format!("expected {expected} argument(s), found {}", arguments.len()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parse the argument of a filter as a count.
+fn count(filter: &str, argument: &str) -> Result<usize> {
+    argument.parse().map_err(|_| Error::Filter {
+        name: filter.to_owned(),
+        reason: format!("expected a count, found \"{argument}\""),
+    })
+}
+
+/// Uppercase the first character of a word and lowercase the rest.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+macro_rules! simple_filter {
+    ($ty:ident, $name:literal, |$i:ident| $body:expr) => {
+        #[derive(Debug)]
+        struct $ty;
+
+        impl Filter for $ty {
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn apply(&self, $i: &str, arguments: &[String]) -> Result<String> {
+                expect_arguments($name, arguments, 0)?;
+                Ok($body)
+            }
+        }
+    };
+}
+
+simple_filter!(Flatten, "flatten", |i| i.replace('\n', " "));
+simple_filter!(Trim, "trim", |i| i.trim().to_owned());
+simple_filter!(Detab, "detab", |i| i.replace('\t', ""));
+simple_filter!(Upper, "upper", |i| i.to_uppercase());
+simple_filter!(Lower, "lower", |i| i.to_lowercase());
+simple_filter!(TrimEnd, "trimend", |i| i.trim_end().to_owned());
+simple_filter!(TrimStart, "trimstart", |i| i.trim_start().to_owned());
+simple_filter!(WordCount, "wordcount", |i| i.split_whitespace().count().to_string());
+simple_filter!(Capitalize, "capitalize", |i| capitalize(i));
+simple_filter!(Title, "title", |i| i.split_inclusive(char::is_whitespace).map(capitalize).collect());
+
+/// The words read per minute by `readingtime`.
+const WORDS_PER_MINUTE: usize = 200;
+
+simple_filter!(ReadingTime, "readingtime", |i| {
+    i.split_whitespace().count().div_ceil(WORDS_PER_MINUTE).to_string()
+});
+
+simple_filter!(StripTags, "striptags", |i| {
+    let mut stripped = String::with_capacity(i.len());
+    let mut in_tag = false;
+
+    for c in i.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => {},
+        }
+    }
+
+    stripped
+});
+
+simple_filter!(Excerpt, "excerpt", |i| {
+    // the first html paragraph, such as in the output of md
+    let html = i.find("<p").and_then(|start| {
+        i[start..].find("</p>").map(|end| &i[start..start + end + "</p>".len()])
+    });
+
+    match html {
+        Some(paragraph) => paragraph.to_owned(),
+        None => i.split("\n\n")
+            .map(str::trim)
+            .find(|p| !p.is_empty())
+            .unwrap_or_default()
+            .to_owned(),
+    }
+});
+
+simple_filter!(Slugify, "slugify", |i| {
+    let mut slug = String::with_capacity(i.len());
+
+    for c in i.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_end_matches('-').to_owned()
+});
+
+/// The alphabet of standard base64.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Format a digest as lowercase hexadecimal.
+fn hex(digest: &[u8]) -> String {
+    digest.iter().fold(String::with_capacity(digest.len() * 2), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+simple_filter!(UrlEncode, "urlencode", |i| {
+    i.bytes().fold(String::with_capacity(i.len()), |mut encoded, b| {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(b as char);
+        } else {
+            let _ = write!(encoded, "%{b:02X}");
+        }
+
+        encoded
+    })
+});
+
+simple_filter!(Base64, "base64", |i| {
+    let mut encoded = String::with_capacity(i.len().div_ceil(3) * 4);
+
+    for chunk in i.as_bytes().chunks(3) {
+        let n = chunk.iter().enumerate()
+            .fold(0u32, |n, (index, b)| n | (*b as u32) << (16 - index * 8));
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - index * 6) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+});
+
+#[derive(Debug)]
+struct Base64Decode;
+
+impl Filter for Base64Decode {
+    fn name(&self) -> &str {
+        "base64decode"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("base64decode", arguments, 0)?;
+
+        let error = |reason: &str| Error::Filter {
+            name: "base64decode".to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        let encoded = i.trim_end().trim_end_matches('=');
+        let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+        let mut n = 0u32;
+        let mut bits = 0;
+
+        for c in encoded.bytes() {
+            let Some(value) = BASE64.iter().position(|b| *b == c) else {
+                return Err(error("content is not base64"));
+            };
+
+            n = n << 6 | value as u32;
+            bits += 6;
+
+            if bits >= 8 {
+                bits -= 8;
+                decoded.push((n >> bits) as u8);
+                n &= (1 << bits) - 1;
+            }
+        }
+
+        String::from_utf8(decoded).map_err(|_| error("decoded content is not utf-8"))
+    }
+}
+
+/// Quote content as a JSON string literal. Markup characters are escaped as
+/// unicode sequences, so the literal is safe within a script element.
+#[derive(Debug)]
+struct Json;
+
+impl Filter for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("json", arguments, 0)?;
+
+        let mut json = String::with_capacity(i.len() + 2);
+        json.push('"');
+
+        for c in i.chars() {
+            match c {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                '\n' => json.push_str("\\n"),
+                '\r' => json.push_str("\\r"),
+                '\t' => json.push_str("\\t"),
+                c if c.is_control() || matches!(c, '<' | '>' | '&' | '\'') => {
+                    let _ = write!(json, "\\u{:04x}", c as u32);
+                },
+                c => json.push(c),
+            }
+        }
+
+        json.push('"');
+        Ok(json)
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+simple_filter!(Xml, "xml", |i| {
+    let mut xml = String::with_capacity(i.len());
+
+    for c in i.chars() {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\'' => xml.push_str("&apos;"),
+            c => xml.push(c),
+        }
+    }
+
+    xml
+});
+
+simple_filter!(Shell, "shell", |i| format!("'{}'", i.replace('\'', "'\\''")));
+simple_filter!(Sha256Hex, "sha256", |i| hex(&Sha256::digest(i)));
+simple_filter!(Md5Hex, "md5", |i| hex(&Md5::digest(i)));
+
+/// The current time in seconds since the Unix epoch, or the value of the
+/// `SOURCE_DATE_EPOCH` environment variable for reproducible builds.
+pub(crate) fn now() -> i64 {
+    std::env::var("SOURCE_DATE_EPOCH").ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64)
+        })
+}
+
+/// Parse a Unix timestamp, an RFC 3339 or RFC 2822 date and time, or a date
+/// alone at midnight, all in UTC unless an offset is given.
+pub(crate) fn timestamp(filter: &str, i: &str) -> Result<DateTime<Utc>> {
+    let i = i.trim();
+
+    let parsed = i.parse::<f64>().ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds.floor() as i64, 0))
+        .or_else(|| DateTime::parse_from_rfc3339(i).ok().map(|d| d.to_utc()))
+        .or_else(|| DateTime::parse_from_rfc2822(i).ok().map(|d| d.to_utc()))
+        .or_else(|| NaiveDateTime::parse_from_str(i, "%Y-%m-%d %H:%M:%S").ok().map(|d| d.and_utc()))
+        .or_else(|| NaiveDate::parse_from_str(i, "%Y-%m-%d").ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc()));
+
+    parsed.ok_or_else(|| Error::Filter {
+        name: filter.to_owned(),
+        reason: format!("expected a Unix timestamp or an RFC 3339 date, found \"{i}\""),
+    })
+}
+
+#[derive(Debug)]
+struct Date;
+
+impl Filter for Date {
+    fn name(&self) -> &str {
+        "date"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("date", arguments, 1)?;
+
+        let date = timestamp("date", i)?;
+        let items = StrftimeItems::new(&arguments[0]).collect::<Vec<Item>>();
+
+        if items.contains(&Item::Error) {
+            return Err(Error::Filter {
+                name: "date".to_owned(),
+                reason: format!("invalid format \"{}\"", arguments[0]),
+            });
+        }
+
+        Ok(date.format_with_items(items.into_iter()).to_string())
+    }
+}
+
+#[derive(Debug)]
+struct Rfc2822;
+
+impl Filter for Rfc2822 {
+    fn name(&self) -> &str {
+        "rfc2822"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("rfc2822", arguments, 0)?;
+        Ok(timestamp("rfc2822", i)?.to_rfc2822())
+    }
+}
+
+#[derive(Debug)]
+struct Rfc3339;
+
+impl Filter for Rfc3339 {
+    fn name(&self) -> &str {
+        "rfc3339"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("rfc3339", arguments, 0)?;
+        Ok(timestamp("rfc3339", i)?.to_rfc3339())
+    }
+}
+
+/// Describe the time between a date and now, such as "3 days ago".
+#[derive(Debug)]
+struct TimeAgo;
+
+impl Filter for TimeAgo {
+    fn name(&self) -> &str {
+        "timeago"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        self.apply_within(i, arguments, &FilterContext::default())
+    }
+
+    fn apply_within(&self, i: &str, arguments: &[String], context: &FilterContext) -> Result<String> {
+        expect_arguments_between("timeago", arguments, 0, 1)?;
+
+        let now = match arguments.first() {
+            Some(now) => timestamp("timeago", now)?.timestamp(),
+            None => context.now,
+        };
+
+        let seconds = now - timestamp("timeago", i)?.timestamp();
+
+        const UNITS: [(i64, &str); 6] = [
+            (60 * 60 * 24 * 365, "year"),
+            (60 * 60 * 24 * 30, "month"),
+            (60 * 60 * 24 * 7, "week"),
+            (60 * 60 * 24, "day"),
+            (60 * 60, "hour"),
+            (60, "minute"),
+        ];
+
+        let Some((n, unit)) = UNITS.into_iter()
+            .find(|(length, _)| seconds.abs() >= *length)
+            .map(|(length, unit)| (seconds.abs() / length, unit)) else {
+            return Ok("just now".to_owned());
+        };
+
+        let plural = if n == 1 { "" } else { "s" };
+
+        Ok(match seconds < 0 {
+            true => format!("in {n} {unit}{plural}"),
+            false => format!("{n} {unit}{plural} ago"),
+        })
+    }
+}
+
+/// The ending appended to truncated content by default.
+const ELLIPSIS: &str = "…";
+
+#[derive(Debug)]
+struct Truncate;
+
+impl Filter for Truncate {
+    fn name(&self) -> &str {
+        "truncate"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments_between("truncate", arguments, 1, 2)?;
+
+        let length = count("truncate", &arguments[0])?;
+        let ending = arguments.get(1).map_or(ELLIPSIS, String::as_str);
+
+        match i.char_indices().nth(length) {
+            Some((end, _)) => Ok(format!("{}{ending}", i[..end].trim_end())),
+            None => Ok(i.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TruncateWords;
+
+impl Filter for TruncateWords {
+    fn name(&self) -> &str {
+        "truncatewords"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments_between("truncatewords", arguments, 1, 2)?;
+
+        let length = count("truncatewords", &arguments[0])?;
+        let ending = arguments.get(1).map_or(ELLIPSIS, String::as_str);
+
+        let words = i.split_whitespace().collect::<Vec<&str>>();
+
+        if words.len() <= length {
+            return Ok(i.to_owned());
+        }
+
+        Ok(format!("{}{ending}", words[..length].join(" ")))
+    }
+}
+
+#[derive(Debug)]
+struct Replace;
+
+impl Filter for Replace {
+    fn name(&self) -> &str {
+        "replace"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("replace", arguments, 2)?;
+
+        if arguments[0].is_empty() {
+            return Err(Error::Filter {
+                name: "replace".to_owned(),
+                reason: "cannot replace an empty string".to_owned(),
+            });
+        }
+
+        Ok(i.replace(&arguments[0], &arguments[1]))
+    }
+}
+
+#[derive(Debug)]
+struct Markdown;
+
+impl Filter for Markdown {
+    fn name(&self) -> &str {
+        "md"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("md", arguments, 0)?;
+        Ok(NfmParser::parse_str(i))
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// Leaves an implemented value as it is. The renderer falls back to the
+/// argument of `default` when there is no value to filter.
+#[derive(Debug)]
+struct DefaultValue;
+
+impl Filter for DefaultValue {
+    fn name(&self) -> &str {
+        "default"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("default", arguments, 1)?;
+        Ok(i.to_owned())
+    }
+}
+
+#[derive(Debug)]
+struct Safe;
+
+impl Filter for Safe {
+    fn name(&self) -> &str {
+        "safe"
+    }
+
+    fn apply(&self, i: &str, arguments: &[String]) -> Result<String> {
+        expect_arguments("safe", arguments, 0)?;
+        Ok(i.to_owned())
+    }
+
+    fn is_safe(&self) -> bool {
+        true
+    }
+}
+
+/// The filters available to templates by name. The default registry holds
+/// the built-in filters.
+///
+/// # Examples
+///
+/// ```rust
+/// use vg_core::FilterRegistry;
+///
+/// let filters = FilterRegistry::default();
+/// let trim = filters.get("trim").unwrap();
+/// assert_eq!("text", trim.apply("  text  ", &[]).unwrap());
+/// ```
+#[derive(Clone, Debug)]
+pub struct FilterRegistry {
+    filters: HashMap<String, Arc<dyn Filter>>,
+}
+
+impl FilterRegistry {
+    /// Construct a registry without any filters.
+    pub fn empty() -> Self {
+        Self { filters: HashMap::new(), }
+    }
+
+    /// Register a filter, replacing any filter of the same name.
+    pub fn insert<F: Filter + 'static>(&mut self, filter: F) {
+        self.filters.insert(filter.name().to_owned(), Arc::new(filter));
+    }
+
+    /// Retrieve a filter by name.
+    pub fn get(&self, name: &str) -> Option<&dyn Filter> {
+        self.filters.get(name).map(|f| f.as_ref())
+    }
+}
+
+impl Default for FilterRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.insert(Flatten);
+        registry.insert(Trim);
+        registry.insert(Detab);
+        registry.insert(Replace);
+        registry.insert(Upper);
+        registry.insert(Lower);
+        registry.insert(Markdown);
+        registry.insert(TrimEnd);
+        registry.insert(TrimStart);
+        registry.insert(Safe);
+        registry.insert(DefaultValue);
+        registry.insert(Truncate);
+        registry.insert(TruncateWords);
+        registry.insert(WordCount);
+        registry.insert(ReadingTime);
+        registry.insert(StripTags);
+        registry.insert(Excerpt);
+        registry.insert(Slugify);
+        registry.insert(Title);
+        registry.insert(Capitalize);
+        registry.insert(UrlEncode);
+        registry.insert(Base64);
+        registry.insert(Base64Decode);
+        registry.insert(Json);
+        registry.insert(Xml);
+        registry.insert(Shell);
+        registry.insert(Sha256Hex);
+        registry.insert(Md5Hex);
+        registry.insert(Date);
+        registry.insert(Rfc2822);
+        registry.insert(Rfc3339);
+        registry.insert(TimeAgo);
+
+        registry
+    }
+}
@@ -0,0 +1,392 @@
+// vg-core::loader: The sources from which vg templates are loaded.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The sources from which vg templates are loaded.
+
+use {
+    crate::{ Error, Result, },
+    std::{
+        collections::{ BTreeMap, HashMap, },
+        fmt::Debug,
+        fs::OpenOptions,
+        io::{ BufRead, BufReader, },
+        path::{ Component, Path, PathBuf, },
+        time::SystemTime,
+    },
+};
+
+/// Resolve the `.` and `..` components of a path without touching the
+/// filesystem.
+pub(crate) fn normalize<P: AsRef<Path>>(p: P) -> PathBuf {
+    let mut normal = PathBuf::new();
+
+    for component in p.as_ref().components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => match normal.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normal.pop();
+                },
+                Some(Component::RootDir | Component::Prefix(_)) => {},
+                _ => normal.push(".."),
+            },
+            c => normal.push(c),
+        }
+    }
+
+    normal
+}
+
+/// The metadata of a loaded path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Metadata {
+    /// Whether the path is a directory rather than a file.
+    pub is_dir: bool,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// When the file was created, or last modified where the filesystem does
+    /// not record when files are created.
+    pub created: SystemTime,
+    pub modified: SystemTime,
+}
+
+/// A source of templates and the directories containing them.
+pub trait Loader: Debug + Send + Sync {
+    /// Read the content of a file.
+    fn read(&self, p: &Path) -> Result<String>;
+
+    /// Retrieve the metadata of a path, or `None` when it does not exist.
+    fn metadata(&self, p: &Path) -> Option<Metadata>;
+
+    /// List the paths directly within a directory along with their metadata,
+    /// skipping those without metadata such as broken symlinks.
+    fn list(&self, p: &Path) -> Result<Vec<(PathBuf, Metadata)>>;
+
+    /// Resolve a path to its real location, following symlinks, or `None`
+    /// when it does not exist.
+    fn canonicalize(&self, p: &Path) -> Option<PathBuf> {
+        self.exists(p).then(|| normalize(p))
+    }
+
+    /// Whether a path exists.
+    fn exists(&self, p: &Path) -> bool {
+        self.metadata(p).is_some()
+    }
+
+    /// Whether a path exists and is a file.
+    fn is_file(&self, p: &Path) -> bool {
+        self.metadata(p).is_some_and(|m| !m.is_dir)
+    }
+
+    /// Whether a path exists and is a directory.
+    fn is_dir(&self, p: &Path) -> bool {
+        self.metadata(p).is_some_and(|m| m.is_dir)
+    }
+}
+
+/// A loader reading from the filesystem.
+///
+/// # Examples
+///
+/// ```rust
+/// use {
+///     vg_core::{ FsLoader, Loader, },
+///     std::path::Path,
+/// };
+///
+/// let loader = FsLoader;
+/// let content = loader.read(Path::new("./test/variable/2/template.jinja")).unwrap();
+/// assert_eq!("{{ test? }}", content);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FsLoader;
+
+impl FsLoader {
+    fn fs_metadata(p: &Path) -> std::io::Result<Metadata> {
+        let metadata = p.metadata()?;
+
+        Ok(Metadata {
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+            created: metadata.created().or_else(|_| metadata.modified())?,
+            modified: metadata.modified()?,
+        })
+    }
+}
+
+impl Loader for FsLoader {
+    fn read(&self, p: &Path) -> Result<String> {
+        if !p.is_file() {
+            return Err(Error::NotAFileError(p.into()));
+        }
+
+        let mut file = OpenOptions::new().read(true).open(p)
+            .map_err(Error::IOError)?;
+        let mut source = String::new();
+        let br = BufReader::new(&mut file);
+        let mut lines = br.lines();
+        let mut dlim = "";
+        while let Some(Ok(line)) = lines.next() {
+            source.push_str(dlim);
+            source.push_str(&line);
+
+            if dlim.is_empty() {
+                dlim = "\n";
+            }
+        }
+
+        Ok(source)
+    }
+
+    fn metadata(&self, p: &Path) -> Option<Metadata> {
+        Self::fs_metadata(p).ok()
+    }
+
+    fn list(&self, p: &Path) -> Result<Vec<(PathBuf, Metadata)>> {
+        let read_dir = p.read_dir().map_err(Error::IOError)?;
+        let mut entries = Vec::new();
+
+        for entry in read_dir {
+            let path = entry.map_err(Error::IOError)?.path();
+
+            // entries such as broken symlinks are not listed
+            if let Ok(metadata) = Self::fs_metadata(&path) {
+                entries.push((path, metadata));
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn canonicalize(&self, p: &Path) -> Option<PathBuf> {
+        p.canonicalize().ok()
+    }
+
+    fn is_file(&self, p: &Path) -> bool {
+        p.is_file()
+    }
+
+    fn is_dir(&self, p: &Path) -> bool {
+        p.is_dir()
+    }
+}
+
+/// A loader reading from a map of paths to content held in memory. Every
+/// ancestor of an inserted path is a directory.
+///
+/// # Examples
+///
+/// ```rust
+/// use vg_core::{ FileCache, MemoryLoader, Parser, };
+///
+/// let loader = MemoryLoader::from_iter([
+///     ("/layout.jinja", "<h1>{{ title }}</h1>"),
+///     ("/page.jinja", "{% extends \"/layout.jinja\" %}{% block title %}Home{% endblock %}"),
+/// ]);
+///
+/// let mut cache = FileCache::enabled().with_loader(loader);
+/// let output = Parser::compile_with_cache("/", "/page.jinja", &mut cache).unwrap();
+/// assert_eq!("<h1>Home</h1>", output);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryLoader {
+    files: BTreeMap<PathBuf, (String, Metadata)>,
+}
+
+impl MemoryLoader {
+    /// Construct an empty in-memory loader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a file, replacing any file at the same path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use {
+    ///     vg_core::{ Loader, MemoryLoader, },
+    ///     std::path::Path,
+    /// };
+    ///
+    /// let mut loader = MemoryLoader::new();
+    /// loader.insert("/pages/home.jinja", "Home");
+    /// assert!(loader.is_dir(Path::new("/pages")));
+    /// assert_eq!("Home", loader.read(Path::new("/pages/./home.jinja")).unwrap());
+    /// ```
+    pub fn insert<P: AsRef<Path>, S: Into<String>>(&mut self, p: P, content: S) {
+        self.insert_with_times(p, content, SystemTime::UNIX_EPOCH, SystemTime::UNIX_EPOCH);
+    }
+
+    /// Insert a file with the given creation and modification times.
+    pub fn insert_with_times<P, S>(&mut self, p: P, content: S, created: SystemTime, modified: SystemTime)
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        let content = content.into();
+
+        let metadata = Metadata {
+            is_dir: false,
+            size: content.len() as u64,
+            created,
+            modified,
+        };
+
+        self.files.insert(normalize(p), (content, metadata));
+    }
+
+    fn dir_metadata() -> Metadata {
+        Metadata {
+            is_dir: true,
+            size: 0,
+            created: SystemTime::UNIX_EPOCH,
+            modified: SystemTime::UNIX_EPOCH,
+        }
+    }
+}
+
+impl<P: AsRef<Path>, S: Into<String>> FromIterator<(P, S)> for MemoryLoader {
+    fn from_iter<I: IntoIterator<Item = (P, S)>>(iter: I) -> Self {
+        let mut loader = Self::new();
+        iter.into_iter().for_each(|(p, s)| loader.insert(p, s));
+        loader
+    }
+}
+
+impl Loader for MemoryLoader {
+    fn read(&self, p: &Path) -> Result<String> {
+        match self.files.get(&normalize(p)) {
+            Some((content, _)) => Ok(content.clone()),
+            None => Err(Error::NotAFileError(p.into())),
+        }
+    }
+
+    fn metadata(&self, p: &Path) -> Option<Metadata> {
+        let path = normalize(p);
+
+        if let Some((_, metadata)) = self.files.get(&path) {
+            return Some(metadata.clone());
+        }
+
+        self.files.keys()
+            .any(|f| f.starts_with(&path))
+            .then(Self::dir_metadata)
+    }
+
+    fn list(&self, p: &Path) -> Result<Vec<(PathBuf, Metadata)>> {
+        let path = normalize(p);
+
+        if !self.is_dir(&path) {
+            return Err(Error::NotADirectoryError(p.into()));
+        }
+
+        let mut entries: Vec<(PathBuf, Metadata)> = Vec::new();
+
+        for (file, (_, metadata)) in &self.files {
+            let Ok(relative) = file.strip_prefix(&path) else {
+                continue;
+            };
+
+            let mut components = relative.components();
+
+            let Some(first) = components.next() else {
+                continue;
+            };
+
+            let entry = p.join(first);
+
+            if components.next().is_none() {
+                entries.push((entry, metadata.clone()));
+            } else if entries.last().is_none_or(|(last, _)| *last != entry) {
+                // files are ordered, so a directory's files are adjacent
+                entries.push((entry, Self::dir_metadata()));
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// A loader layering one loader over another. Paths in the upper loader hide
+/// the same paths in the lower loader.
+///
+/// # Examples
+///
+/// ```rust
+/// use {
+///     vg_core::{ FsLoader, Loader, MemoryLoader, OverlayLoader, },
+///     std::path::Path,
+/// };
+///
+/// let upper = MemoryLoader::from_iter([
+///     ("./test/variable/2/template.jinja", "Overridden"),
+/// ]);
+/// let loader = OverlayLoader::new(upper, FsLoader);
+///
+/// let path = Path::new("./test/variable/2/template.jinja");
+/// assert_eq!("Overridden", loader.read(path).unwrap());
+///
+/// let path = Path::new("./test/variable/1/template.jinja");
+/// assert!(loader.is_file(path));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OverlayLoader<U: Loader, L: Loader> {
+    upper: U,
+    lower: L,
+}
+
+impl<U: Loader, L: Loader> OverlayLoader<U, L> {
+    /// Construct a loader layering `upper` over `lower`.
+    pub fn new(upper: U, lower: L) -> Self {
+        Self { upper, lower, }
+    }
+}
+
+impl<U: Loader, L: Loader> Loader for OverlayLoader<U, L> {
+    fn read(&self, p: &Path) -> Result<String> {
+        if self.upper.is_file(p) {
+            self.upper.read(p)
+        } else {
+            self.lower.read(p)
+        }
+    }
+
+    fn metadata(&self, p: &Path) -> Option<Metadata> {
+        self.upper.metadata(p).or_else(|| self.lower.metadata(p))
+    }
+
+    fn canonicalize(&self, p: &Path) -> Option<PathBuf> {
+        self.upper.canonicalize(p).or_else(|| self.lower.canonicalize(p))
+    }
+
+    fn list(&self, p: &Path) -> Result<Vec<(PathBuf, Metadata)>> {
+        let upper = self.upper.is_dir(p);
+        let lower = self.lower.is_dir(p);
+
+        let mut entries = HashMap::new();
+
+        if lower {
+            entries.extend(self.lower.list(p)?.into_iter().map(|(p, m)| (normalize(&p), (p, m))));
+        }
+
+        if upper || !lower {
+            entries.extend(self.upper.list(p)?.into_iter().map(|(p, m)| (normalize(&p), (p, m))));
+        }
+
+        Ok(entries.into_values().collect())
+    }
+}
@@ -0,0 +1,1237 @@
+// vg-core::render: The renderer of parsed vg templates.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The renderer of parsed vg templates.
+
+use {
+    crate::{
+        CompileOptions,
+        Error,
+        FileCache,
+        Offence,
+        Result,
+        Value,
+        data::Format,
+        filter::{ self, FilterContext, },
+        loader::{ Metadata, normalize, },
+        template::{
+            Assignment,
+            Body,
+            CallTag,
+            Condition,
+            DataTag,
+            FilterCall,
+            ForTag,
+            IfTag,
+            ImportTag,
+            IncludeTag,
+            Iterable,
+            MacroTag,
+            Node,
+            Operand,
+            Operator,
+            PathValue,
+            SetTag,
+            Sort,
+            SortKey,
+            Template,
+            Variable,
+            WithTag,
+        },
+    },
+    std::{
+        cmp::Ordering,
+        collections::{ BTreeMap, HashMap, HashSet, },
+        path::{ Component, Path, PathBuf, },
+        sync::Arc,
+        time::SystemTime,
+    },
+    nfm_core::Parser as NfmParser,
+};
+
+/// A macro defined while rendering, along with where it was defined.
+#[derive(Debug)]
+pub(crate) struct Macro {
+    tag: MacroTag,
+    /// The directory relative paths within the macro are based on.
+    directory: PathBuf,
+    /// The path of the template defining the macro.
+    path: Option<PathBuf>,
+    /// The prefix the macro was defined within, which its body is rendered
+    /// within.
+    prefix: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Context {
+    pub(crate) implementations: HashMap<String, Value>,
+    /// The names of implementations given by the caller rather than rendered
+    /// from a template, which are autoescaped.
+    pub(crate) untrusted: HashSet<String>,
+    macros: HashMap<String, Arc<Macro>>,
+    prefix: Option<String>,
+    /// Whether names not implemented within the prefix fall back to their
+    /// unprefixed implementation, as within macros and imported templates.
+    fallback: bool,
+}
+
+impl Context {
+    fn implement<V: Into<Value>>(&mut self, name: String, value: V) {
+        self.untrusted.remove(&name);
+        self.implementations.insert(name, value.into());
+    }
+
+    /// Retrieve the value of a name, resolving the rest of a dotted name
+    /// within the value of the longest implemented part. Null values are not
+    /// implemented.
+    fn get(&self, name: &str) -> Option<&Value> {
+        let value = self.implementations.get(name).or_else(|| {
+            name.rmatch_indices('.').find_map(|(idx, _)| {
+                self.implementations.get(&name[..idx])
+                    .and_then(|v| v.get(&name[idx + 1..]))
+            })
+        });
+
+        value.filter(|v| !v.is_null())
+    }
+
+    /// Whether a name, or the part of a dotted name containing it, was given
+    /// by the caller.
+    fn is_untrusted(&self, name: &str) -> bool {
+        self.untrusted.contains(name) || name.rmatch_indices('.')
+            .any(|(idx, _)| self.untrusted.contains(&name[..idx]))
+    }
+
+    fn prefixed(&self, name: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_owned(),
+        }
+    }
+
+    /// The name an implementation is read from, which is the prefixed name
+    /// unless only the unprefixed name is implemented and falls back, or the
+    /// name is `self.__file`.
+    fn resolve(&self, name: &str) -> String {
+        // the compiled template is described the same within every prefix
+        if name == "self.__file" || name.starts_with("self.__file.") {
+            return name.to_owned();
+        }
+
+        let prefixed = self.prefixed(name);
+
+        if self.fallback && self.get(&prefixed).is_none() && self.get(name).is_some() {
+            return name.to_owned();
+        }
+
+        prefixed
+    }
+
+    /// Retrieve a macro by its prefixed name, falling back to its unprefixed
+    /// name like implementations.
+    fn get_macro(&self, name: &str) -> Option<&Arc<Macro>> {
+        self.macros.get(&self.prefixed(name))
+            .or_else(|| self.fallback.then(|| self.macros.get(name)).flatten())
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// The offence of a filter which is not registered.
+fn unknown_filter(call: &FilterCall, source: &str) -> Offence {
+    Offence {
+        path: None,
+        line: call.line,
+        column: call.column,
+        snippet: source.to_owned(),
+        reason: format!("unknown filter \"{}\"", call.name),
+    }
+}
+
+/// Collect every filter call within nodes along with the source of its
+/// variable or tag, including those in bodies which may never be rendered.
+fn filter_calls<'a>(nodes: &'a [Node], calls: &mut Vec<(&'a FilterCall, &'a str)>) {
+    fn variable<'a>(v: &'a Variable, calls: &mut Vec<(&'a FilterCall, &'a str)>) {
+        calls.extend(v.filters.iter().map(|f| (f, v.source.as_str())));
+    }
+
+    fn path<'a>(p: &'a PathValue, calls: &mut Vec<(&'a FilterCall, &'a str)>) {
+        if let PathValue::Variable(v) = p {
+            variable(v, calls);
+        }
+    }
+
+    fn assignment<'a>(a: &'a Assignment, calls: &mut Vec<(&'a FilterCall, &'a str)>) {
+        calls.extend(a.filters.iter().map(|f| (f, a.source.as_str())));
+    }
+
+    for node in nodes {
+        match node {
+            Node::Content(_) | Node::Call(_) => {},
+            Node::Variable(v) => variable(v, calls),
+            Node::If(i) => {
+                filter_calls(&i.then.nodes, calls);
+
+                if let Some(otherwise) = &i.otherwise {
+                    filter_calls(&otherwise.nodes, calls);
+                }
+            },
+            Node::For(f) => {
+                if let Iterable::Path(p) = &f.iterable {
+                    path(p, calls);
+                }
+
+                filter_calls(&f.body.nodes, calls);
+
+                if let Some(otherwise) = &f.otherwise {
+                    filter_calls(&otherwise.nodes, calls);
+                }
+            },
+            Node::Block(b) => filter_calls(&b.body.nodes, calls),
+            Node::Include(i) => path(&i.path, calls),
+            Node::Autoescape(a) => filter_calls(&a.body.nodes, calls),
+            Node::Macro(m) => filter_calls(&m.body.nodes, calls),
+            Node::Import(i) => path(&i.path, calls),
+            Node::Data(d) => path(&d.path, calls),
+            Node::Set(s) => assignment(&s.assignment, calls),
+            Node::With(w) => {
+                assignment(&w.assignment, calls);
+                filter_calls(&w.body.nodes, calls);
+            },
+        }
+    }
+}
+
+/// Compare strings such that runs of digits compare by their number, so
+/// `item9` is before `item10`. Strings which are otherwise equal, such as
+/// `a01` and `a1`, compare as written.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (mut rest_a, mut rest_b) = (a, b);
+
+    loop {
+        let ordering = match (rest_a.chars().next(), rest_b.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, after_a) = rest_a.split_at(digits(rest_a));
+                let (y, after_b) = rest_b.split_at(digits(rest_b));
+                (rest_a, rest_b) = (after_a, after_b);
+
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            },
+            (Some(x), Some(y)) => {
+                (rest_a, rest_b) = (&rest_a[x.len_utf8()..], &rest_b[y.len_utf8()..]);
+                x.cmp(&y)
+            },
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+/// Compare the values of a sort key, numbers by their value and anything
+/// else in natural order. Items without a value are first.
+fn value_cmp(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.total_cmp(b),
+        (Some(a), Some(b)) => natural_cmp(&a.to_string(), &b.to_string()),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// The value of an item for a sort key, with the times of files compared at
+/// full precision.
+enum SortValue {
+    /// The position of an item after files are ordered by name.
+    Index(usize),
+    Time(SystemTime),
+    Value(Value),
+}
+
+/// Compare the values of a sort key. Items without a value are first.
+fn sort_cmp(a: Option<&SortValue>, b: Option<&SortValue>) -> Ordering {
+    match (a, b) {
+        (Some(SortValue::Index(a)), Some(SortValue::Index(b))) => a.cmp(b),
+        (Some(SortValue::Time(a)), Some(SortValue::Time(b))) => a.cmp(b),
+        (Some(SortValue::Value(a)), Some(SortValue::Value(b))) => value_cmp(Some(a), Some(b)),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// A pseudorandom number for an item of a shuffle, the same for every
+/// compilation with the same seed.
+fn shuffled(seed: u64, index: usize) -> u64 {
+    // splitmix64
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Sort the items of a `for` tag by each key in turn. Files are sorted by
+/// their name and metadata, and the items of a list keep their order unless
+/// sorted by a field.
+fn sort_items(keys: &[SortKey], items: Iterations, variable: &str) -> Vec<Context> {
+    let name = |item: &Context| item.get(&format!("{variable}.__file.name")).map(Value::to_string);
+
+    // files are listed in no particular order, so they are first ordered by
+    // name, which shuffles and ties between other keys keep
+    let mut items = items.into_iter()
+        .map(|(item, metadata)| (name(&item), item, metadata))
+        .collect::<Vec<(Option<String>, Context, Option<Metadata>)>>();
+
+    items.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    let mut keyed = items.into_iter()
+        .enumerate()
+        .map(|(index, (_, item, metadata))| {
+            let values = keys.iter()
+                .map(|key| match &key.sort {
+                    Sort::Name => Some(SortValue::Index(index)),
+                    Sort::Created => metadata.as_ref().map(|m| SortValue::Time(m.created)),
+                    Sort::Modified => metadata.as_ref().map(|m| SortValue::Time(m.modified)),
+                    Sort::Field(field) => item.get(&item.prefixed(field)).cloned().map(SortValue::Value),
+                    Sort::Shuffle(seed) => Some(SortValue::Value(Value::from(shuffled(*seed, index) >> 12))),
+                })
+                .collect::<Vec<Option<SortValue>>>();
+
+            (values, item)
+        })
+        .collect::<Vec<(Vec<Option<SortValue>>, Context)>>();
+
+    keyed.sort_by(|(a, _), (b, _)| keys.iter()
+        .zip(a.iter().zip(b))
+        .map(|(key, (a, b))| match key.reverse {
+            true => sort_cmp(b.as_ref(), a.as_ref()),
+            false => sort_cmp(a.as_ref(), b.as_ref()),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal));
+
+    keyed.into_iter().map(|(_, item)| item).collect()
+}
+
+fn handle_trim(s: &mut String, trim_start: bool, trim_end: bool) {
+    if trim_start && trim_end {
+        let mut out = s.trim().to_owned();
+        std::mem::swap(&mut out, s);
+    } else if trim_start {
+        let mut out = s.trim_start().to_owned();
+        std::mem::swap(&mut out, s);
+    } else if trim_end {
+        let mut out = s.trim_end().to_owned();
+        std::mem::swap(&mut out, s);
+    }
+}
+
+/// The contexts of the items of a `for` tag, along with the metadata of files.
+type Iterations = Vec<(Context, Option<Metadata>)>;
+
+struct ForItem {
+    path: PathBuf,
+    metadata: Metadata,
+}
+
+pub(crate) struct Renderer<'a> {
+    root_dir: &'a Path,
+    cache: &'a mut FileCache,
+    options: &'a CompileOptions,
+    /// Whether untrusted implementations are escaped where they are rendered.
+    autoescape: bool,
+    /// The path of the template being rendered.
+    path: Option<PathBuf>,
+    /// The templates being rendered, from the compiled template to the most
+    /// deeply included.
+    stack: Vec<PathBuf>,
+    /// The number of macro calls being rendered within one another.
+    calls: usize,
+    offences: Vec<Offence>,
+    /// The context filters are applied within.
+    filter_context: FilterContext,
+}
+
+impl<'a> Renderer<'a> {
+    pub(crate) fn new(root_dir: &'a Path, cache: &'a mut FileCache, options: &'a CompileOptions) -> Result<Self> {
+        if !cache.loader().is_dir(root_dir) {
+            return Err(Error::NotADirectoryError(root_dir.into()));
+        }
+
+        Ok(Self {
+            root_dir,
+            cache,
+            options,
+            autoescape: options.autoescape,
+            path: None,
+            stack: Vec::new(),
+            calls: 0,
+            offences: Vec::new(),
+            filter_context: FilterContext::default(),
+        })
+    }
+
+    /// Render a template, failing with every offence found in strict mode.
+    pub(crate) fn render(mut self, template: &Template, context: &mut Context) -> Result<String> {
+        if let Some(path) = template.path() {
+            self.stack.push(normalize(path));
+
+            if let Some(metadata) = self.cache.loader().metadata(path) {
+                context.implement("self.__file".to_owned(), self.file_value(path, &metadata));
+            }
+        }
+
+        // the time is fixed for the whole compilation, unless an implemented
+        // `now` is not a time
+        match context.get("now") {
+            Some(now) => if let Ok(now) = filter::timestamp("now", &now.to_string()) {
+                self.filter_context.now = now.timestamp();
+            },
+            None => context.implement("now".to_owned(), self.filter_context.now),
+        }
+
+        let output = self.template(template, context)?;
+
+        if !self.offences.is_empty() {
+            return Err(Error::Strict(self.offences));
+        }
+
+        Ok(output)
+    }
+
+    fn offend(&mut self, offence: Offence) {
+        let offence = Offence { path: self.path.clone(), ..offence };
+
+        // templates rendered more than once offend more than once
+        if !self.offences.contains(&offence) {
+            self.offences.push(offence);
+        }
+    }
+
+    /// Load and render the template at a path, failing when it is already being
+    /// rendered or the stack is too deep.
+    fn template_at(&mut self, path: &Path, context: &mut Context) -> Result<String> {
+        let normal = normalize(path);
+
+        if self.stack.contains(&normal) || self.stack.len() >= self.options.max_depth {
+            let mut stack = self.stack.clone();
+            let is_cycle = stack.contains(&normal);
+            stack.push(normal);
+
+            return Err(if is_cycle {
+                Error::Cycle(stack)
+            } else {
+                Error::MaxDepth(stack)
+            });
+        }
+
+        self.stack.push(normal);
+
+        let result = self.cache.template(path)
+            .and_then(|template| self.template(&template, context))
+            .map_err(|e| self.nested(e));
+
+        self.stack.pop();
+
+        result
+    }
+
+    /// Attach the stack to an error raised within an included template.
+    fn nested(&self, e: Error) -> Error {
+        match e {
+            Error::IsIgnored |
+                Error::Cycle(_) |
+                Error::MaxDepth(_) |
+                Error::Nested { .. } => e,
+            e if self.stack.len() < 2 => e,
+            e => Error::Nested { stack: self.stack.clone(), error: Box::new(e), },
+        }
+    }
+
+    fn template(&mut self, template: &Template, context: &mut Context) -> Result<String> {
+        if template.is_ignored() {
+            return Err(Error::IsIgnored);
+        }
+
+        let mut path = template.path().map(PathBuf::from);
+        std::mem::swap(&mut self.path, &mut path);
+        let result = self.template_nodes(template, context);
+        std::mem::swap(&mut self.path, &mut path);
+
+        result
+    }
+
+    fn template_nodes(&mut self, template: &Template, context: &mut Context) -> Result<String> {
+        if self.options.strict {
+            template.offences().iter()
+                .for_each(|o| self.offend(o.clone()));
+
+            let mut calls = Vec::new();
+            filter_calls(template.nodes(), &mut calls);
+
+            for (call, source) in calls {
+                if self.options.filters.get(&call.name).is_none() {
+                    self.offend(unknown_filter(call, source));
+                }
+            }
+        }
+
+        for (key, value) in template.front_matter() {
+            context.implement(context.prefixed(key), value.clone());
+        }
+
+        let extends = match template.extends() {
+            Some(e) => self.path(e, template.directory(), context)?,
+            None => None,
+        };
+
+        let mut output = String::new();
+        self.nodes(template.nodes(), template.directory(), context, &mut output)?;
+
+        // the output of an extending template is discarded, only its
+        // implementations are kept
+        match extends {
+            Some(extends) => self.template_at(&extends, context),
+            None => Ok(output),
+        }
+    }
+
+    fn nodes(&mut self, nodes: &[Node], directory: &Path, context: &mut Context, output: &mut String) -> Result<()> {
+        // whether the previous tag trims the whitespace following it
+        let mut trim = false;
+
+        for node in nodes {
+            match node {
+                Node::Content(c) if trim => output.push_str(c.trim_start()),
+                Node::Content(c) => output.push_str(c),
+                Node::Variable(v) => {
+                    let (content, untrusted) = self.variable(v, context)?;
+
+                    if self.autoescape && untrusted {
+                        output.push_str(&escape_html(&content));
+                    } else {
+                        output.push_str(&content);
+                    }
+                },
+                Node::If(i) => self.if_tag(i, directory, context, output)?,
+                Node::For(f) => self.for_tag(f, directory, context, output)?,
+                Node::Block(b) => {
+                    let content = self.body(&b.body, directory, context.clone())?;
+                    context.implement(context.prefixed(&b.name), content);
+                },
+                Node::Include(i) => self.include(i, directory, context, output)?,
+                Node::Autoescape(a) => {
+                    let mut autoescape = a.enabled;
+                    std::mem::swap(&mut self.autoescape, &mut autoescape);
+
+                    let mut content = String::new();
+                    let result = self.nodes(&a.body.nodes, directory, context, &mut content);
+
+                    std::mem::swap(&mut self.autoescape, &mut autoescape);
+                    result?;
+
+                    handle_trim(&mut content, a.body.trim_start, a.body.trim_end);
+                    output.push_str(&content);
+                },
+                Node::Macro(m) => {
+                    let definition = Macro {
+                        tag: m.clone(),
+                        directory: directory.into(),
+                        path: self.path.clone(),
+                        prefix: context.prefix.clone(),
+                    };
+
+                    context.macros.insert(context.prefixed(&m.name), Arc::new(definition));
+                },
+                Node::Call(c) => self.call(c, context, output)?,
+                Node::Import(i) => self.import(i, directory, context)?,
+                Node::Data(d) => self.data_tag(d, directory, context)?,
+                Node::Set(s) => self.assign(&s.assignment, context)?,
+                Node::With(w) => self.with_tag(w, directory, context, output)?,
+            }
+
+            trim = match node {
+                Node::Include(IncludeTag { trim, .. }) |
+                    Node::Call(CallTag { trim, .. }) |
+                    Node::Import(ImportTag { trim, .. }) |
+                    Node::Data(DataTag { trim, .. }) |
+                    Node::Set(SetTag { trim, .. }) => *trim,
+                _ => false,
+            };
+        }
+
+        Ok(())
+    }
+
+    fn body(&mut self, body: &Body, directory: &Path, mut context: Context) -> Result<String> {
+        let mut output = String::new();
+        self.nodes(&body.nodes, directory, &mut context, &mut output)?;
+        handle_trim(&mut output, body.trim_start, body.trim_end);
+        Ok(output)
+    }
+
+    /// Retrieve an implementation along with whether it was given to the
+    /// compiler, so must be escaped when autoescaping.
+    fn implementation(name: &str, context: &Context) -> Option<(String, bool)> {
+        let name = context.resolve(name);
+
+        context.get(&name)
+            .map(|v| (v.to_string(), context.is_untrusted(&name)))
+    }
+
+    /// Apply filters to a value, falling back to the argument of `default`
+    /// while there is no value. Safe filters mark the value as trusted.
+    fn filter(
+        &mut self, mut value: Option<(String, bool)>, calls: &[FilterCall], source: &str,
+        context: &Context,
+    ) -> Result<Option<(String, bool)>> {
+        // resolve every filter, even when there is nothing to apply them to
+        let mut filters = Vec::with_capacity(calls.len());
+
+        for call in calls {
+            let Some(filter) = self.options.filters.get(&call.name) else {
+                let Offence { line, column, snippet, reason, .. } = unknown_filter(call, source);
+
+                // strict mode has offended for every unknown filter already
+                if self.options.strict {
+                    continue;
+                }
+
+                return Err(Error::Parse {
+                    path: self.path.clone(),
+                    line,
+                    column,
+                    snippet,
+                    reason,
+                });
+            };
+
+            filters.push((filter, call));
+        }
+
+        for (filter, call) in filters {
+            match &mut value {
+                Some((content, untrusted)) => {
+                    let arguments = call.arguments.iter()
+                        .map(|a| match a {
+                            Operand::Literal(s) | Operand::Variable(s) => s.to_owned(),
+                        })
+                        .collect::<Vec<String>>();
+
+                    *content = filter.apply_within(content, &arguments, &self.filter_context)?;
+                    *untrusted &= !filter.is_safe();
+                },
+                // fall back to the argument of default while nothing is implemented
+                None if call.name == "default" => value = match call.arguments.as_slice() {
+                    [Operand::Literal(l)] => Some((l.to_owned(), false)),
+                    [Operand::Variable(v)] => Self::implementation(v, context),
+                    arguments => return Err(Error::Filter {
+                        name: call.name.clone(),
+                        reason: format!("expected 1 argument(s), found {}", arguments.len()),
+                    }),
+                },
+                None => {},
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Render a variable along with whether its content must be escaped when
+    /// autoescaping.
+    fn variable(&mut self, variable: &Variable, context: &Context) -> Result<(String, bool)> {
+        let value = Self::implementation(&variable.name, context);
+
+        if let Some(value) = self.filter(value, &variable.filters, &variable.source, context)? {
+            return Ok(value);
+        }
+
+        if variable.nullable {
+            return Ok((String::new(), false));
+        }
+
+        if self.options.strict {
+            self.offend(Offence {
+                path: None,
+                line: variable.line,
+                column: variable.column,
+                snippet: variable.source.clone(),
+                reason: format!("\"{}\" is not implemented", variable.name),
+            });
+        }
+
+        Ok((variable.source.clone(), false))
+    }
+
+    /// Implement the name of an assignment, or leave it unimplemented when
+    /// its value is not implemented.
+    fn assign(&mut self, assignment: &Assignment, context: &mut Context) -> Result<()> {
+        let name = context.prefixed(&assignment.name);
+
+        // unfiltered variables keep their value rather than its text
+        if let (Operand::Variable(v), true) = (&assignment.value, assignment.filters.is_empty()) {
+            let source = context.resolve(v);
+
+            match context.get(&source).cloned() {
+                Some(value) => {
+                    let untrusted = context.is_untrusted(&source);
+                    context.implement(name.clone(), value);
+
+                    if untrusted {
+                        context.untrusted.insert(name);
+                    }
+                },
+                None => {
+                    context.implementations.remove(&name);
+                },
+            }
+
+            return Ok(());
+        }
+
+        let value = match &assignment.value {
+            Operand::Literal(l) => Some((l.to_owned(), false)),
+            Operand::Variable(v) => Self::implementation(v, context),
+        };
+
+        let value = self.filter(value, &assignment.filters, &assignment.source, context)?;
+
+        match value {
+            Some((content, untrusted)) => {
+                context.implement(name.clone(), content);
+
+                if untrusted {
+                    context.untrusted.insert(name);
+                }
+            },
+            None => {
+                context.implementations.remove(&name);
+            },
+        }
+
+        Ok(())
+    }
+
+    fn path(&mut self, path: &PathValue, directory: &Path, context: &Context) -> Result<Option<PathBuf>> {
+        let path = match path {
+            PathValue::Literal(l) => l.to_owned(),
+            PathValue::Variable(v) => self.variable(v, context)?.0,
+        };
+
+        if path.is_empty() {
+            return Ok(None);
+        }
+
+        self.sandbox(FileCache::rebase_path(self.root_dir, directory, path))
+            .map(Some)
+    }
+
+    /// Reject a path outside of the directories allowed by the sandbox.
+    fn sandbox(&self, path: PathBuf) -> Result<PathBuf> {
+        let Some(sandbox) = &self.options.sandbox else {
+            return Ok(path);
+        };
+
+        let roots = std::iter::once(self.root_dir)
+            .chain(sandbox.extra_roots.iter().map(PathBuf::as_path));
+
+        let within = |path: &Path, root: &Path| path.strip_prefix(root)
+            .is_ok_and(|rest| rest.components().next() != Some(Component::ParentDir));
+
+        let normal = normalize(&path);
+
+        if !roots.clone().any(|root| within(&normal, &normalize(root))) {
+            return Err(Error::PathOutsideRoot(path));
+        }
+
+        if sandbox.follow_symlinks {
+            return Ok(path);
+        }
+
+        // paths which do not exist have nothing to follow
+        let loader = self.cache.loader();
+
+        if let Some(real) = loader.canonicalize(&path) {
+            let is_within = roots.filter_map(|root| loader.canonicalize(root))
+                .any(|root| within(&real, &root));
+
+            if !is_within {
+                return Err(Error::PathOutsideRoot(path));
+            }
+        }
+
+        Ok(path)
+    }
+
+    fn operand(operand: &Operand, context: &Context) -> Option<String> {
+        match operand {
+            Operand::Literal(l) => Some(l.to_owned()),
+            Operand::Variable(v) => context.get(&context.resolve(v)).map(Value::to_string),
+        }
+    }
+
+    fn condition(condition: &Condition, context: &Context) -> bool {
+        let implementation = |v: &str| context.get(&context.resolve(v));
+
+        match condition {
+            // false is not a value which exists
+            Condition::Existence(v) => implementation(v).is_some_and(|i| *i != Value::Bool(false)),
+            Condition::Emptiness(v) => implementation(v).is_none_or(|i| i.is_empty()),
+            Condition::Not(c) => !Self::condition(c, context),
+            Condition::And(a, b) => Self::condition(a, context) && Self::condition(b, context),
+            Condition::Or(a, b) => Self::condition(a, context) || Self::condition(b, context),
+            Condition::Comparison { left, operator, right, } => {
+                let left = Self::operand(left, context);
+                let right = Self::operand(right, context);
+
+                // unimplemented variables are equal to nothing
+                let (Some(left), Some(right)) = (left, right) else {
+                    return *operator == Operator::Ne;
+                };
+
+                let numbers = left.trim().parse::<f64>()
+                    .and_then(|l| right.trim().parse::<f64>().map(|r| (l, r)));
+
+                match (operator, numbers) {
+                    (Operator::Eq, _) => left == right,
+                    (Operator::Ne, _) => left != right,
+                    (Operator::Lt, Ok((l, r))) => l < r,
+                    (Operator::Le, Ok((l, r))) => l <= r,
+                    (Operator::Gt, Ok((l, r))) => l > r,
+                    (Operator::Ge, Ok((l, r))) => l >= r,
+                    _ => false,
+                }
+            },
+        }
+    }
+
+    fn if_tag(&mut self, tag: &IfTag, directory: &Path, context: &mut Context, output: &mut String) -> Result<()> {
+        if Self::condition(&tag.condition, context) {
+            output.push_str(&self.body(&tag.then, directory, context.clone())?);
+        } else if let Some(otherwise) = &tag.otherwise {
+            output.push_str(&self.body(otherwise, directory, context.clone())?);
+        }
+
+        Ok(())
+    }
+
+    fn with_tag(&mut self, tag: &WithTag, directory: &Path, context: &mut Context, output: &mut String) -> Result<()> {
+        let mut with_ctx = context.clone();
+        self.assign(&tag.assignment, &mut with_ctx)?;
+        output.push_str(&self.body(&tag.body, directory, with_ctx)?);
+
+        Ok(())
+    }
+
+    fn include(&mut self, tag: &IncludeTag, directory: &Path, context: &mut Context, output: &mut String) -> Result<()> {
+        let Some(path) = self.path(&tag.path, directory, context)? else {
+            return Ok(());
+        };
+
+        // raw included content is directly injected into output
+        if tag.raw {
+            let content = self.cache.get(&path).map_err(|e| {
+                self.stack.push(normalize(&path));
+                let e = self.nested(e);
+                self.stack.pop();
+                e
+            })?;
+
+            if tag.markdown {
+                output.push_str(&NfmParser::parse_str(&content));
+            } else {
+                output.push_str(&content);
+            }
+
+            return Ok(());
+        }
+
+        // if as name is not empty, combine with existing prefixing
+        let mut this_prefix = match &tag.alias {
+            Some(alias) => Some(context.prefixed(alias)),
+            None => context.prefix.clone(),
+        };
+
+        // only the body of a macro falls back to the implementations of its
+        // caller, not the templates it includes
+        let fallback = std::mem::replace(&mut context.fallback, false);
+        std::mem::swap(&mut context.prefix, &mut this_prefix);
+        let result = self.template_at(&path, context);
+        std::mem::swap(&mut context.prefix, &mut this_prefix);
+        context.fallback = fallback;
+
+        let mut content = match result {
+            Ok(content) => content,
+            Err(Error::IsIgnored) => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        // if was markdown, parse output
+        if tag.markdown {
+            content = NfmParser::parse_str(&content);
+        }
+
+        // if had 'as' keyword, put new output into an implementation
+        match (&tag.alias, this_prefix) {
+            (Some(_), Some(prefix)) => {
+                context.implement(prefix, content);
+            },
+            _ => output.push_str(&content),
+        }
+
+        Ok(())
+    }
+
+    fn call(&mut self, call: &CallTag, context: &Context, output: &mut String) -> Result<()> {
+        let Some(definition) = context.get_macro(&call.name).cloned() else {
+            if self.options.strict {
+                self.offend(Offence {
+                    path: None,
+                    line: call.line,
+                    column: call.column,
+                    snippet: call.source.clone(),
+                    reason: format!("macro \"{}\" is not defined", call.name),
+                });
+            }
+
+            output.push_str(&call.source);
+            return Ok(());
+        };
+
+        let parameters = &definition.tag.parameters;
+
+        if call.arguments.len() > parameters.len() {
+            return Err(Error::Parse {
+                path: self.path.clone(),
+                line: call.line,
+                column: call.column,
+                snippet: call.source.clone(),
+                reason: format!(
+                    "macro \"{}\" takes {} argument(s), found {}",
+                    call.name, parameters.len(), call.arguments.len()
+                ),
+            });
+        }
+
+        if self.calls >= self.options.max_depth {
+            return Err(Error::MaxDepth(self.stack.clone()));
+        }
+
+        // names not implemented within the macro's prefix fall back to those
+        // of the caller
+        let mut macro_ctx = context.clone();
+        macro_ctx.prefix = definition.prefix.clone();
+        macro_ctx.fallback = true;
+
+        // parameters without an argument are not implemented
+        for (idx, parameter) in parameters.iter().enumerate() {
+            let name = macro_ctx.prefixed(parameter);
+
+            match call.arguments.get(idx) {
+                Some(Operand::Literal(l)) => macro_ctx.implement(name, l.to_owned()),
+                Some(Operand::Variable(v)) => {
+                    let source = context.resolve(v);
+
+                    match context.get(&source) {
+                        Some(value) => {
+                            macro_ctx.implement(name.clone(), value.clone());
+
+                            if context.is_untrusted(&source) {
+                                macro_ctx.untrusted.insert(name);
+                            }
+                        },
+                        None => {
+                            macro_ctx.implementations.remove(&name);
+                        },
+                    }
+                },
+                None => {
+                    macro_ctx.implementations.remove(&name);
+                },
+            }
+        }
+
+        let mut path = definition.path.clone();
+        std::mem::swap(&mut self.path, &mut path);
+        self.calls += 1;
+
+        let result = self.body(&definition.tag.body, &definition.directory, macro_ctx);
+
+        self.calls -= 1;
+        std::mem::swap(&mut self.path, &mut path);
+
+        output.push_str(&result?);
+
+        Ok(())
+    }
+
+    /// Define the macros of another template under the alias of an import.
+    fn import(&mut self, tag: &ImportTag, directory: &Path, context: &mut Context) -> Result<()> {
+        let Some(path) = self.path(&tag.path, directory, context)? else {
+            return Ok(());
+        };
+
+        // the imported template is rendered within the caller's context, but
+        // only its macros are kept
+        let mut imported = context.clone();
+        imported.prefix = Some(context.prefixed(&tag.alias));
+        imported.fallback = true;
+
+        match self.template_at(&path, &mut imported) {
+            Ok(_) | Err(Error::IsIgnored) => {},
+            Err(e) => return Err(e),
+        }
+
+        context.macros.extend(imported.macros);
+
+        Ok(())
+    }
+
+    /// List the files of a `for` tag, which are sorted along with the other
+    /// items of a `for` tag.
+    fn for_items(&self, path: PathBuf) -> Result<(Vec<ForItem>, bool)> {
+        let loader = self.cache.loader();
+
+        if loader.is_file(&path) {
+            let Some(metadata) = loader.metadata(&path) else {
+                return Err(Error::NotAFileError(path));
+            };
+
+            let item = ForItem { path, metadata, };
+
+            return Ok((vec![ item ], false));
+        }
+
+        if !loader.is_dir(&path) {
+            return Ok((Vec::new(), false));
+        }
+
+        let entries = loader.list(&path)?;
+
+        let items = entries.into_iter()
+            .filter(|(_, metadata)| !metadata.is_dir)
+            .map(|(path, metadata)| ForItem { path, metadata, })
+            .collect::<Vec<ForItem>>();
+
+        // mark as loop to set loop context implementations
+        let is_loop = !items.is_empty();
+
+        Ok((items, is_loop))
+    }
+
+    /// The `__file` value describing a file, with its path relative to the
+    /// root directory and its times in seconds since the Unix epoch.
+    fn file_value(&self, path: &Path, metadata: &Metadata) -> Value {
+        let normal = normalize(path);
+        let relative = match normal.strip_prefix(normalize(self.root_dir)) {
+            Ok(relative) => Path::new("/").join(relative),
+            Err(_) => normal.clone(),
+        };
+
+        let text = |s: Option<&std::ffi::OsStr>| s
+            .map(|s| Value::from(s.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let seconds = |t: SystemTime| t.duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(Value::Null, |d| Value::from(d.as_secs()));
+
+        Value::from(BTreeMap::from([
+            ("name", text(normal.file_name())),
+            ("stem", text(normal.file_stem())),
+            ("extension", text(normal.extension())),
+            ("path", Value::from(relative.to_string_lossy().into_owned())),
+            ("size", Value::from(metadata.size)),
+            ("created", seconds(metadata.created)),
+            ("modified", seconds(metadata.modified)),
+        ]))
+    }
+
+    /// Render each file of a `for` tag within the prefix of the variable and
+    /// implement the variable with its output.
+    fn for_files(&mut self, path: PathBuf, context: &mut Context, variable: &str) -> Result<(Iterations, bool)> {
+        let (files, is_loop) = self.for_items(path)?;
+
+        // render every item first so that ignored items are not counted
+        let mut items = Vec::new();
+
+        for ForItem { path, metadata, .. } in files {
+            let path = self.sandbox(path)?;
+
+            let mut item_ctx = context.clone();
+            item_ctx.prefix = Some(variable.to_owned());
+            item_ctx.fallback = false;
+            item_ctx.implement(format!("{variable}.__file"), self.file_value(&path, &metadata));
+
+            let item_content = match self.template_at(&path, &mut item_ctx) {
+                Ok(content) => content,
+                Err(Error::IsIgnored) => continue,
+                Err(e) => return Err(e),
+            };
+
+            item_ctx.prefix = context.prefix.clone();
+            item_ctx.fallback = context.fallback;
+            item_ctx.implement(variable.to_owned(), item_content);
+
+            items.push((item_ctx, Some(metadata)));
+        }
+
+        Ok((items, is_loop))
+    }
+
+    /// Read and parse a data file, attaching the stack to any error.
+    fn data(&mut self, path: &Path, format: Format) -> Result<Value> {
+        self.cache.get(path)
+            .and_then(|content| format.parse(path, &content))
+            .map_err(|e| {
+                self.stack.push(normalize(path));
+                let e = self.nested(e);
+                self.stack.pop();
+                e
+            })
+    }
+
+    /// Implement the alias of a `data` tag with the value of a data file.
+    fn data_tag(&mut self, tag: &DataTag, directory: &Path, context: &mut Context) -> Result<()> {
+        let Some(path) = self.path(&tag.path, directory, context)? else {
+            return Ok(());
+        };
+
+        let Some(format) = Format::of(&path) else {
+            return Err(Error::Data {
+                path,
+                reason: "expected a .json, .ron, .toml or .csv file".to_owned(),
+            });
+        };
+
+        let data = self.data(&path, format)?;
+        context.implement(context.prefixed(&tag.alias), data);
+
+        Ok(())
+    }
+
+    /// Implement the variable of a `for` tag with each item of a list, or
+    /// with any other value alone.
+    fn for_values(value: Option<&Value>, untrusted: bool, context: &Context, variable: &str) -> (Iterations, bool) {
+        let (values, is_loop) = match value {
+            Some(Value::List(list)) => (list.clone(), !list.is_empty()),
+            Some(value) => (vec![ value.clone() ], false),
+            None => (Vec::new(), false),
+        };
+
+        let items = values.into_iter()
+            .map(|value| {
+                let mut item_ctx = context.clone();
+                item_ctx.implement(variable.to_owned(), value);
+
+                if untrusted {
+                    item_ctx.untrusted.insert(variable.to_owned());
+                }
+
+                (item_ctx, None)
+            })
+            .collect();
+
+        (items, is_loop)
+    }
+
+    fn for_tag(&mut self, tag: &ForTag, directory: &Path, context: &mut Context, output: &mut String) -> Result<()> {
+        let variable = context.prefixed(&tag.variable);
+
+        let (items, is_loop) = match &tag.iterable {
+            Iterable::Path(path) => match self.path(path, directory, context)? {
+                // each item of a data file is an iteration
+                Some(path) if self.cache.loader().is_file(&path) => match Format::of(&path) {
+                    Some(format) => {
+                        let data = format.iterable(self.data(&path, format)?);
+                        Self::for_values(Some(&data), false, context, &variable)
+                    },
+                    None => self.for_files(path, context, &variable)?,
+                },
+                Some(path) => self.for_files(path, context, &variable)?,
+                None => (Vec::new(), false),
+            },
+            Iterable::Variable(name) => {
+                let source = context.resolve(name);
+                let untrusted = context.is_untrusted(&source);
+                Self::for_values(context.get(&source), untrusted, context, &variable)
+            },
+        };
+
+        let items = sort_items(&tag.sort, items, &variable).into_iter()
+            .skip(tag.offset)
+            .take(tag.limit.unwrap_or(usize::MAX))
+            .collect::<Vec<Context>>();
+
+        if items.is_empty() {
+            if let Some(otherwise) = &tag.otherwise {
+                output.push_str(&self.body(otherwise, directory, context.clone())?);
+            }
+
+            return Ok(());
+        }
+
+        let size = items.len();
+        let max = size - 1;
+        let loop_prefix = context.prefixed("loop");
+
+        for (idx, mut item_ctx) in items.into_iter().enumerate() {
+            // handle loop context
+            if is_loop {
+                let implementations = &mut item_ctx.implementations;
+
+                implementations.insert(loop_prefix.clone(), variable.as_str().into());
+                implementations.insert(format!("{loop_prefix}.index"), idx.into());
+                implementations.insert(format!("{loop_prefix}.position"), (idx + 1).into());
+
+                if idx == 0 {
+                    implementations.insert(format!("{loop_prefix}.first"), true.into());
+                } else {
+                    implementations.remove(&format!("{loop_prefix}.first"));
+                }
+
+                if idx == max {
+                    implementations.insert(format!("{loop_prefix}.last"), true.into());
+                } else {
+                    implementations.remove(&format!("{loop_prefix}.last"));
+                }
+
+                implementations.insert(format!("{loop_prefix}.size"), size.into());
+                implementations.insert(format!("{loop_prefix}.max"), max.into());
+            }
+
+            output.push_str(&self.body(&tag.body, directory, item_ctx)?);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1795 @@
+// vg-core::template: The tokenizer, parser and syntax tree of vg templates.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The tokenizer, parser and syntax tree of vg templates.
+
+use {
+    crate::{
+        CompileOptions,
+        Error,
+        FileCache,
+        Offence,
+        Result,
+        Value,
+        data::front_matter,
+        render::{ Context, Renderer, },
+    },
+    std::{
+        collections::BTreeMap,
+        path::{ Path, PathBuf, },
+        sync::Arc,
+    },
+};
+
+const TAG: [&str; 2] = [
+    "{%",
+    "%}"
+];
+
+const VARIABLE: [&str; 2] = [
+    "{{",
+    "}}"
+];
+
+const PATH: &str = "\"";
+
+const COMMENT: [&str; 2] = [
+    "{#",
+    "#}"
+];
+
+const CURLY_ESCAPE: [&str; 2] = [
+    "\\{",
+    "\\}",
+];
+
+const PERC_ESCAPE: &str = "\\%";
+
+const HASH_ESCAPE: &str = "\\#";
+
+const PIPE: &str = "|";
+
+const TRIM: &str = "-";
+
+const TRIM_END: &str = "-%}";
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+/// A filter called on the value of a variable, resolved by name when the
+/// template is rendered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterCall {
+    pub name: String,
+    /// The quoted strings, numbers and names following the name. Filters are
+    /// given the text of each argument, while `default` resolves a name to
+    /// the variable it names.
+    pub arguments: Vec<Operand>,
+    /// The line of the filter in the template, starting at 1.
+    pub line: usize,
+    /// The column of the filter in the template, starting at 1.
+    pub column: usize,
+}
+
+/// A variable, such as `{{ name? | trim }}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    /// The name of the variable, relative to the current prefix.
+    pub name: String,
+    /// Whether the variable renders nothing when it is not implemented.
+    pub nullable: bool,
+    /// The filters applied to the implementation, in order.
+    pub filters: Vec<FilterCall>,
+    /// The source text of the variable, rendered when it is not implemented.
+    pub source: String,
+    /// The line of the variable in the template, starting at 1.
+    pub line: usize,
+    /// The column of the variable in the template, starting at 1.
+    pub column: usize,
+}
+
+/// A path value given to an `include`, `extends` or `for` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathValue {
+    Literal(String),
+    Variable(Variable),
+}
+
+/// The collection iterated by a `for` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Iterable {
+    /// A file, or the files of a directory.
+    Path(PathValue),
+    /// The items of a list implementing a variable.
+    Variable(String),
+}
+
+/// A side of a comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// A quoted string or a number.
+    Literal(String),
+    /// The name of a variable.
+    Variable(String),
+}
+
+/// The operator of a comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// The condition checked by an `if` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    /// Whether the variable is implemented.
+    Existence(String),
+    /// Whether the variable is unimplemented or implemented as nothing.
+    Emptiness(String),
+    /// Compare two operands. `<`, `<=`, `>` and `>=` only hold when both
+    /// operands are numbers.
+    Comparison {
+        left: Operand,
+        operator: Operator,
+        right: Operand,
+    },
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+/// The method by which the items of a `for` tag are sorted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sort {
+    /// By file name, or by position for the items of a list.
+    Name,
+    Created,
+    Modified,
+    /// By the value of a name implemented for each item, such as `post.date`.
+    Field(String),
+    /// Into an order determined by a seed.
+    Shuffle(u64),
+}
+
+/// One key of the sort of a `for` tag, compared when the keys before it are
+/// equal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortKey {
+    pub sort: Sort,
+    pub reverse: bool,
+}
+
+/// The content between an opening and closing tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Body {
+    pub nodes: Vec<Node>,
+    /// Whether leading whitespace is trimmed from the rendered body.
+    pub trim_start: bool,
+    /// Whether trailing whitespace is trimmed from the rendered body.
+    pub trim_end: bool,
+}
+
+/// An `{% if %}` tag. Each `{% elif %}` is parsed as an if tag alone within
+/// the `otherwise` body of the tag before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IfTag {
+    pub condition: Condition,
+    pub then: Body,
+    pub otherwise: Option<Body>,
+}
+
+/// A `{% for %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForTag {
+    pub variable: String,
+    pub iterable: Iterable,
+    pub sort: Vec<SortKey>,
+    /// The number of sorted items skipped.
+    pub offset: usize,
+    /// The number of items rendered after the offset, or every item.
+    pub limit: Option<usize>,
+    pub body: Body,
+    pub otherwise: Option<Body>,
+}
+
+/// A `{% block %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockTag {
+    pub name: String,
+    pub body: Body,
+}
+
+/// An `{% autoescape %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoescapeTag {
+    /// Whether variables within the body are autoescaped.
+    pub enabled: bool,
+    pub body: Body,
+}
+
+/// An `{% include %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IncludeTag {
+    pub path: PathValue,
+    pub raw: bool,
+    pub markdown: bool,
+    /// The name given with `as`.
+    pub alias: Option<String>,
+    /// Whether whitespace following the tag is trimmed.
+    pub trim: bool,
+}
+
+/// A `{% macro %}` tag, defining a body rendered wherever the macro is
+/// called.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MacroTag {
+    pub name: String,
+    /// The names the arguments of a call are implemented as, in order.
+    pub parameters: Vec<String>,
+    pub body: Body,
+}
+
+/// A call of a macro, such as `{% call card "Hello" page.url %}` or
+/// `{{ card("Hello", page.url) }}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CallTag {
+    pub name: String,
+    pub arguments: Vec<Operand>,
+    /// The source text of the call, rendered when the macro is not defined.
+    pub source: String,
+    /// The line of the call in the template, starting at 1.
+    pub line: usize,
+    /// The column of the call in the template, starting at 1.
+    pub column: usize,
+    /// Whether whitespace following the tag is trimmed.
+    pub trim: bool,
+}
+
+/// An `{% import %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportTag {
+    pub path: PathValue,
+    /// The name given with `as`, prefixing the imported macros.
+    pub alias: String,
+    /// Whether whitespace following the tag is trimmed.
+    pub trim: bool,
+}
+
+/// A `{% data %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataTag {
+    pub path: PathValue,
+    /// The name given with `as`, implemented by the data.
+    pub alias: String,
+    /// Whether whitespace following the tag is trimmed.
+    pub trim: bool,
+}
+
+/// The assignment of a `{% set %}` or `{% with %}` tag, such as
+/// `name = other.var | upper`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    pub name: String,
+    /// A quoted string, a number or the name of a variable.
+    pub value: Operand,
+    /// The filters applied to the value, in order.
+    pub filters: Vec<FilterCall>,
+    /// The source text of the tag making the assignment.
+    pub source: String,
+}
+
+/// A `{% set %}` tag.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetTag {
+    pub assignment: Assignment,
+    /// Whether whitespace following the tag is trimmed.
+    pub trim: bool,
+}
+
+/// A `{% with %}` tag, whose assignment only lives within its body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithTag {
+    pub assignment: Assignment,
+    pub body: Body,
+}
+
+/// A node of a parsed template.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Content(String),
+    Variable(Variable),
+    If(IfTag),
+    For(ForTag),
+    Block(BlockTag),
+    Include(IncludeTag),
+    Autoescape(AutoescapeTag),
+    Macro(MacroTag),
+    Call(CallTag),
+    Import(ImportTag),
+    Set(SetTag),
+    With(WithTag),
+    Data(DataTag),
+}
+
+#[derive(Debug)]
+enum TagKind {
+    Extends(PathValue),
+    Ignore,
+    Include(IncludeTag),
+    For {
+        variable: String,
+        iterable: Iterable,
+        sort: Vec<SortKey>,
+        offset: usize,
+        limit: Option<usize>,
+        trim: bool,
+    },
+    If {
+        condition: Condition,
+        trim: bool,
+    },
+    Block {
+        name: String,
+        trim: bool,
+    },
+    Else {
+        trim_before: bool,
+        trim_after: bool,
+    },
+    Elif {
+        condition: Condition,
+        trim_before: bool,
+        trim_after: bool,
+    },
+    EndIf {
+        trim: bool,
+    },
+    EndFor {
+        trim: bool,
+    },
+    EndBlock {
+        name: Option<String>,
+        trim: bool,
+    },
+    Autoescape {
+        enabled: bool,
+        trim: bool,
+    },
+    EndAutoescape {
+        trim: bool,
+    },
+    Macro {
+        name: String,
+        parameters: Vec<String>,
+        trim: bool,
+    },
+    EndMacro {
+        trim: bool,
+    },
+    Call(CallTag),
+    Import(ImportTag),
+    Data(DataTag),
+    Set(SetTag),
+    With {
+        assignment: Assignment,
+        trim: bool,
+    },
+    EndWith {
+        trim: bool,
+    },
+}
+
+#[derive(Debug)]
+struct Tag {
+    kind: TagKind,
+    source: String,
+    position: usize,
+}
+
+#[derive(Debug)]
+enum Token {
+    Content(String),
+    Variable(Variable),
+    Call(CallTag),
+    Tag(Tag),
+}
+
+fn offence<R: Into<String>>(src: &str, start: usize, position: usize, reason: R) -> Offence {
+    let before = &src[..position];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+    // the snippet runs to the first closing delimiter on the line
+    let rest = &src[start..];
+    let rest = &rest[..rest.find('\n').unwrap_or(rest.len())];
+    let end = [TAG[1], VARIABLE[1], COMMENT[1]].into_iter()
+        .filter_map(|d| rest.find(d).map(|i| i + d.len()))
+        .min()
+        .unwrap_or(rest.len());
+
+    Offence {
+        path: None,
+        line,
+        column,
+        snippet: rest[..end].to_owned(),
+        reason: reason.into(),
+    }
+}
+
+fn parse_error<R: Into<String>>(src: &str, start: usize, position: usize, reason: R) -> Error {
+    let Offence { path, line, column, snippet, reason, } = offence(src, start, position, reason);
+    Error::Parse { path, line, column, snippet, reason, }
+}
+
+struct Lexer<'a> {
+    start: usize,
+    position: usize,
+    /// The last located position with its line and column.
+    located: (usize, usize, usize),
+    src: &'a str,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { start: 0, position: 0, located: (0, 1, 1), src, }
+    }
+
+    /// Lex the front matter between `---` lines at the top of the source. The
+    /// source is left as is when there is no closing line or the lines between
+    /// are not front matter, such as markdown beginning with a rule.
+    fn front_matter(&mut self) -> BTreeMap<String, Value> {
+        let Some(rest) = self.src.strip_prefix("---\n")
+            .or_else(|| self.src.strip_prefix("---\r\n")) else {
+            return BTreeMap::new();
+        };
+
+        let open = self.src.len() - rest.len();
+        let mut offset = open;
+
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == "---" {
+                let Some(matter) = front_matter(&self.src[open..offset]) else {
+                    return BTreeMap::new();
+                };
+
+                self.position = offset + line.len();
+                self.start = self.position;
+
+                return matter;
+            }
+
+            offset += line.len();
+        }
+
+        BTreeMap::new()
+    }
+
+    fn location(&mut self, position: usize) -> (usize, usize) {
+        if position < self.located.0 {
+            self.located = (0, 1, 1);
+        }
+
+        let (from, mut line, mut column) = self.located;
+
+        for c in self.src[from..position].chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+
+        self.located = (position, line, column);
+
+        (line, column)
+    }
+
+    fn source(&self) -> &'a str {
+        &self.src[self.position..]
+    }
+
+    fn is_empty(&self) -> bool {
+        self.source().is_empty()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.source().starts_with(s)
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.position += n;
+    }
+
+    fn error<T, R: Into<String>>(&self, reason: R) -> Result<T> {
+        Err(parse_error(self.src, self.start, self.position, reason))
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.starts_with(s) {
+            self.advance(s.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        if !self.eat(s) {
+            return self.error(format!("expected \"{s}\""));
+        }
+
+        Ok(())
+    }
+
+    fn trim_start(&mut self) {
+        while self.eat(" ") || self.eat("\t") {}
+    }
+
+    fn trim_end(&mut self) -> bool {
+        // only a dash directly before the closing tag trims
+        if self.starts_with(TRIM_END) {
+            self.advance(TRIM.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> &'a str {
+        let source = self.source();
+        let len = source.find(|c| !f(c)).unwrap_or(source.len());
+        self.advance(len);
+        &source[..len]
+    }
+
+    fn word(&mut self) -> &'a str {
+        self.take_while(|c| c.is_ascii_lowercase())
+    }
+
+    fn name(&mut self) -> &'a str {
+        let source = self.source();
+        let mut len = 0;
+
+        for c in source.chars() {
+            if !is_name_char(c) || source[len..].starts_with(TRIM_END) {
+                break;
+            }
+
+            len += c.len_utf8();
+        }
+
+        self.advance(len);
+        &source[..len]
+    }
+
+    fn variable_name(&mut self) -> Result<&'a str> {
+        let position = self.position;
+        let name = self.name();
+
+        if name.is_empty() {
+            return self.error("expected a variable name");
+        }
+
+        if name.starts_with('.') || name.ends_with('.') {
+            self.position = position;
+            return self.error(format!("\"{name}\" cannot begin or end with \".\""));
+        }
+
+        Ok(name)
+    }
+
+    fn escaped(&mut self) -> Option<char> {
+        for escape in [CURLY_ESCAPE[0], CURLY_ESCAPE[1], PERC_ESCAPE, HASH_ESCAPE] {
+            if self.eat(escape) {
+                return escape[1..].chars().next();
+            }
+        }
+
+        None
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>> {
+        let mut content = String::new();
+
+        while !self.is_empty() {
+            self.start = self.position;
+
+            if self.eat(COMMENT[0]) {
+                match self.source().find(COMMENT[1]) {
+                    Some(idx) => self.advance(idx + COMMENT[1].len()),
+                    None => return self.error("comment is never closed"),
+                }
+
+                continue;
+            }
+
+            if let Some(c) = self.escaped() {
+                content.push(c);
+                continue;
+            }
+
+            if !content.is_empty() &&
+                (self.starts_with(VARIABLE[0]) || self.starts_with(TAG[0]))
+            {
+                break;
+            }
+
+            if self.starts_with(VARIABLE[0]) {
+                return self.expression().map(Some);
+            } else if self.starts_with(TAG[0]) {
+                return self.tag().map(|t| Some(Token::Tag(t)));
+            }
+
+            let c = self.source().chars().next().unwrap();
+            content.push(c);
+            self.advance(c.len_utf8());
+        }
+
+        if content.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Token::Content(content)))
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str> {
+        self.expect(PATH)?;
+
+        let s = self.take_while(|c| c != '"');
+
+        if !self.eat(PATH) {
+            return self.error("string is never closed");
+        }
+
+        Ok(s)
+    }
+
+    fn filter(&mut self) -> Result<FilterCall> {
+        let position = self.position;
+
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+
+        if name.is_empty() {
+            return self.error("expected a filter");
+        }
+
+        let mut arguments = Vec::new();
+
+        loop {
+            self.trim_start();
+
+            if self.starts_with(PATH) {
+                arguments.push(Operand::Literal(self.string()?.to_owned()));
+                continue;
+            }
+
+            if self.starts_with(TAG[1]) || self.starts_with(TRIM_END) {
+                break;
+            }
+
+            let argument = self.take_while(|c| !c.is_whitespace() && c != '|' && c != '}');
+
+            if argument.is_empty() {
+                break;
+            } else if argument.parse::<f64>().is_ok() {
+                arguments.push(Operand::Literal(argument.to_owned()));
+            } else {
+                arguments.push(Operand::Variable(argument.to_owned()));
+            }
+        }
+
+        let (line, column) = self.location(position);
+
+        Ok(FilterCall { name: name.to_owned(), arguments, line, column, })
+    }
+
+    /// Lex a variable, or a macro call such as `{{ card("Hello") }}`.
+    fn expression(&mut self) -> Result<Token> {
+        let start = self.position;
+        let (line, column) = self.location(start);
+
+        self.advance(VARIABLE[0].len());
+        self.trim_start();
+
+        let name = self.variable_name()?;
+
+        if !self.eat("(") {
+            self.position = start;
+            return self.variable().map(Token::Variable);
+        }
+
+        let arguments = self.call_arguments()?;
+
+        self.trim_start();
+        self.expect(VARIABLE[1])?;
+
+        Ok(Token::Call(CallTag {
+            name: name.to_owned(),
+            arguments,
+            source: self.src[start..self.position].to_owned(),
+            line,
+            column,
+            trim: false,
+        }))
+    }
+
+    /// Lex the comma-separated arguments of a call up to the closing
+    /// parenthesis.
+    fn call_arguments(&mut self) -> Result<Vec<Operand>> {
+        let mut arguments = Vec::new();
+
+        self.trim_start();
+
+        if self.eat(")") {
+            return Ok(arguments);
+        }
+
+        loop {
+            arguments.push(self.operand()?);
+
+            self.trim_start();
+
+            if self.eat(")") {
+                return Ok(arguments);
+            }
+
+            self.expect(",")?;
+            self.trim_start();
+        }
+    }
+
+    fn variable(&mut self) -> Result<Variable> {
+        let start = self.position;
+        let (line, column) = self.location(start);
+
+        self.advance(VARIABLE[0].len());
+        self.trim_start();
+
+        let name = self.variable_name()?;
+
+        self.trim_start();
+
+        // check for nullability
+        let nullable = self.eat("?");
+
+        self.trim_start();
+
+        let mut filters = Vec::new();
+
+        while self.eat(PIPE) {
+            self.trim_start();
+            filters.push(self.filter()?);
+            self.trim_start();
+        }
+
+        self.expect(VARIABLE[1])?;
+
+        Ok(Variable {
+            name: name.to_owned(),
+            nullable,
+            filters,
+            source: self.src[start..self.position].to_owned(),
+            line,
+            column,
+        })
+    }
+
+    fn path(&mut self) -> Result<PathValue> {
+        if !self.eat(PATH) {
+            return self.error("expected a path");
+        }
+
+        let path = if self.starts_with(VARIABLE[0]) {
+            PathValue::Variable(self.variable()?)
+        } else {
+            let path = self.take_while(|c| c != '"');
+
+            if path.is_empty() {
+                self.position -= PATH.len();
+                return self.error("path cannot be empty");
+            }
+
+            PathValue::Literal(path.to_owned())
+        };
+
+        if !self.eat(PATH) {
+            return self.error("path is never closed");
+        }
+
+        Ok(path)
+    }
+
+    fn include(&mut self) -> Result<TagKind> {
+        // can be included raw
+        let raw = self.eat("raw");
+        self.trim_start();
+        let markdown = self.eat("md");
+        self.trim_start();
+
+        let path = self.path()?;
+
+        self.trim_start();
+
+        let position = self.position;
+
+        let alias = if self.eat("as") {
+            // "as" keyword not allowed in conjunction with raw
+            if raw {
+                self.position = position;
+                return self.error("raw includes cannot be named with \"as\"");
+            }
+
+            self.trim_start();
+
+            let alias = self.name();
+
+            if alias.is_empty() {
+                return self.error("expected a name after \"as\"");
+            }
+
+            self.trim_start();
+
+            Some(alias.to_owned())
+        } else {
+            None
+        };
+
+        let trim = self.trim_end();
+
+        Ok(TagKind::Include(IncludeTag { path, raw, markdown, alias, trim, }))
+    }
+
+    fn for_tag(&mut self) -> Result<TagKind> {
+        // first value is the variable name
+        let variable = self.name();
+
+        if variable.is_empty() {
+            return self.error("expected a variable name");
+        }
+
+        self.trim_start();
+
+        // next value should be "in"
+        self.expect("in")?;
+
+        self.trim_start();
+
+        // next value is the path to the collection, or a list
+        let iterable = if self.starts_with(PATH) {
+            Iterable::Path(self.path()?)
+        } else {
+            Iterable::Variable(self.variable_name()?.to_owned())
+        };
+
+        self.trim_start();
+
+        let sort = if self.eat(PIPE) {
+            self.sort_keys()?
+        } else {
+            vec![ SortKey { sort: Sort::Name, reverse: false, } ]
+        };
+
+        let mut offset = 0;
+        let mut limit = None;
+
+        loop {
+            self.trim_start();
+
+            if self.eat("limit") {
+                limit = Some(self.count()?);
+            } else if self.eat("offset") {
+                offset = self.count()?;
+            } else {
+                break;
+            }
+        }
+
+        let trim = self.trim_end();
+
+        Ok(TagKind::For { variable: variable.to_owned(), iterable, sort, offset, limit, trim, })
+    }
+
+    /// Lex the number following `limit` or `offset`.
+    fn count(&mut self) -> Result<usize> {
+        self.trim_start();
+
+        let position = self.position;
+        let count = self.take_while(|c| c.is_ascii_digit());
+
+        count.parse().or_else(|_| {
+            self.position = position;
+            self.error("expected a count")
+        })
+    }
+
+    /// Lex the comma separated sort keys following the pipe of a `for` tag.
+    fn sort_keys(&mut self) -> Result<Vec<SortKey>> {
+        let mut keys = Vec::new();
+
+        loop {
+            self.trim_start();
+
+            let reverse = self.eat("!");
+            let position = self.position;
+
+            let sort = match self.name() {
+                "" => return self.error("expected a sort method"),
+                "name" => Sort::Name,
+                "created" => Sort::Created,
+                "modified" => Sort::Modified,
+                "shuffle" => {
+                    self.trim_start();
+
+                    let position = self.position;
+                    let seed = self.take_while(|c| c.is_ascii_digit());
+
+                    match seed {
+                        "" => Sort::Shuffle(0),
+                        seed => Sort::Shuffle(seed.parse().or_else(|_| {
+                            self.position = position;
+                            self.error(format!("expected a seed, found \"{seed}\""))
+                        })?),
+                    }
+                },
+                name if name.starts_with('.') || name.ends_with('.') => {
+                    self.position = position;
+                    return self.error(format!("\"{name}\" cannot begin or end with \".\""));
+                },
+                name => Sort::Field(name.to_owned()),
+            };
+
+            keys.push(SortKey { sort, reverse, });
+
+            self.trim_start();
+
+            if !self.eat(",") {
+                return Ok(keys);
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        if self.starts_with(PATH) {
+            return Ok(Operand::Literal(self.string()?.to_owned()));
+        }
+
+        let name = self.variable_name()?;
+
+        if name.parse::<f64>().is_ok() {
+            Ok(Operand::Literal(name.to_owned()))
+        } else {
+            Ok(Operand::Variable(name.to_owned()))
+        }
+    }
+
+    fn operator(&mut self) -> Option<Operator> {
+        let operators = [
+            ("==", Operator::Eq),
+            ("!=", Operator::Ne),
+            ("<=", Operator::Le),
+            (">=", Operator::Ge),
+            ("<", Operator::Lt),
+            (">", Operator::Gt),
+        ];
+
+        operators.into_iter()
+            .find(|(s, _)| self.eat(s))
+            .map(|(_, operator)| operator)
+    }
+
+    /// Eat a keyword which is not the beginning of a longer name.
+    fn keyword(&mut self, k: &str) -> bool {
+        let matches = self.starts_with(k) &&
+            !self.source()[k.len()..].starts_with(is_name_char);
+
+        if matches {
+            self.advance(k.len());
+        }
+
+        matches
+    }
+
+    fn comparison(&mut self) -> Result<Condition> {
+        let position = self.position;
+        let left = self.operand()?;
+
+        self.trim_start();
+
+        if let Some(operator) = self.operator() {
+            self.trim_start();
+            let right = self.operand()?;
+            self.trim_start();
+            return Ok(Condition::Comparison { left, operator, right, });
+        }
+
+        let Operand::Variable(variable) = left else {
+            self.position = position;
+            return self.error("expected a variable name");
+        };
+
+        // check for non-default condition
+        if self.keyword("empty") {
+            self.trim_start();
+            return Ok(Condition::Emptiness(variable));
+        }
+
+        let position = self.position;
+
+        if self.keyword("not") {
+            self.trim_start();
+
+            if self.keyword("empty") {
+                self.trim_start();
+                return Ok(Condition::Not(Box::new(Condition::Emptiness(variable))));
+            }
+
+            // the "not" belongs to whatever follows
+            self.position = position;
+        }
+
+        Ok(Condition::Existence(variable))
+    }
+
+    fn unary(&mut self) -> Result<Condition> {
+        if self.eat("!") || self.keyword("not") {
+            self.trim_start();
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+
+        if self.eat("(") {
+            self.trim_start();
+            let condition = self.condition()?;
+            self.expect(")")?;
+            self.trim_start();
+            return Ok(condition);
+        }
+
+        self.comparison()
+    }
+
+    fn conjunction(&mut self) -> Result<Condition> {
+        let mut condition = self.unary()?;
+
+        while self.keyword("and") {
+            self.trim_start();
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn condition(&mut self) -> Result<Condition> {
+        let mut condition = self.conjunction()?;
+
+        while self.keyword("or") {
+            self.trim_start();
+            condition = Condition::Or(Box::new(condition), Box::new(self.conjunction()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn macro_tag(&mut self) -> Result<TagKind> {
+        let name = self.variable_name()?.to_owned();
+
+        let mut parameters = Vec::new();
+
+        loop {
+            self.trim_start();
+
+            let parameter = self.name();
+
+            if parameter.is_empty() {
+                break;
+            }
+
+            parameters.push(parameter.to_owned());
+        }
+
+        Ok(TagKind::Macro { name, parameters, trim: self.trim_end(), })
+    }
+
+    fn call(&mut self, line: usize, column: usize) -> Result<TagKind> {
+        let name = self.variable_name()?.to_owned();
+
+        let mut arguments = Vec::new();
+
+        loop {
+            self.trim_start();
+
+            if self.starts_with(TAG[1]) || self.starts_with(TRIM_END) {
+                break;
+            }
+
+            arguments.push(self.operand()?);
+        }
+
+        let trim = self.trim_end();
+
+        // the source is known once the whole tag is lexed
+        Ok(TagKind::Call(CallTag { name, arguments, source: String::new(), line, column, trim, }))
+    }
+
+    /// Lex a path followed by the name given with `as`, and whether following
+    /// whitespace is trimmed.
+    fn path_as(&mut self) -> Result<(PathValue, String, bool)> {
+        let path = self.path()?;
+
+        self.trim_start();
+        self.expect("as")?;
+        self.trim_start();
+
+        let alias = self.name();
+
+        if alias.is_empty() {
+            return self.error("expected a name after \"as\"");
+        }
+
+        self.trim_start();
+
+        Ok((path, alias.to_owned(), self.trim_end()))
+    }
+
+    fn assignment(&mut self) -> Result<Assignment> {
+        let name = self.variable_name()?.to_owned();
+
+        self.trim_start();
+        self.expect("=")?;
+        self.trim_start();
+
+        let value = self.operand()?;
+
+        self.trim_start();
+
+        let mut filters = Vec::new();
+
+        while self.eat(PIPE) {
+            self.trim_start();
+            filters.push(self.filter()?);
+            self.trim_start();
+        }
+
+        // the source is known once the whole tag is lexed
+        Ok(Assignment { name, value, filters, source: String::new(), })
+    }
+
+    fn if_tag(&mut self) -> Result<TagKind> {
+        let condition = self.condition()?;
+
+        self.trim_start();
+
+        let trim = self.trim_end();
+
+        Ok(TagKind::If { condition, trim, })
+    }
+
+    fn tag(&mut self) -> Result<Tag> {
+        let start = self.position;
+        let (line, column) = self.location(start);
+
+        self.advance(TAG[0].len());
+
+        let trim = self.eat(TRIM);
+
+        self.trim_start();
+
+        let keyword_position = self.position;
+        let keyword = self.word();
+
+        if keyword.is_empty() {
+            return self.error("expected a tag name");
+        }
+
+        self.trim_start();
+
+        // only closing tags and include may trim the preceding content
+        if trim && matches!(keyword, "extends" | "ignore" | "for" | "if" | "block" | "autoescape" | "macro" | "set" | "with") {
+            self.position = keyword_position;
+            return self.error(format!("\"{keyword}\" cannot trim preceding content"));
+        }
+
+        let kind = match keyword {
+            "else" => TagKind::Else { trim_before: trim, trim_after: self.trim_end(), },
+            "elif" => {
+                let condition = self.condition()?;
+                TagKind::Elif { condition, trim_before: trim, trim_after: self.trim_end(), }
+            },
+            "endif" => TagKind::EndIf { trim, },
+            "endfor" => TagKind::EndFor { trim, },
+            "endblock" => {
+                // only endblock can be followed by a name
+                let name = self.name();
+                self.trim_start();
+                TagKind::EndBlock {
+                    name: Some(name).filter(|n| !n.is_empty()).map(str::to_owned),
+                    trim,
+                }
+            },
+            "include" => self.include()?,
+            "extends" => {
+                // this keyword accepts a path value
+                let path = self.path()?;
+                self.trim_start();
+                TagKind::Extends(path)
+            },
+            "ignore" => TagKind::Ignore,
+            "for" => self.for_tag()?,
+            "if" => self.if_tag()?,
+            "block" => {
+                let name = self.name();
+
+                if name.is_empty() {
+                    return self.error("expected a block name");
+                }
+
+                self.trim_start();
+
+                TagKind::Block { name: name.to_owned(), trim: self.trim_end(), }
+            },
+            "autoescape" => {
+                let position = self.position;
+
+                let enabled = match self.word() {
+                    "on" => true,
+                    "off" => false,
+                    _ => {
+                        self.position = position;
+                        return self.error("expected \"on\" or \"off\"");
+                    },
+                };
+
+                self.trim_start();
+
+                TagKind::Autoescape { enabled, trim: self.trim_end(), }
+            },
+            "endautoescape" => TagKind::EndAutoescape { trim, },
+            "macro" => self.macro_tag()?,
+            "endmacro" => TagKind::EndMacro { trim, },
+            "call" => self.call(line, column)?,
+            "import" => {
+                let (path, alias, trim) = self.path_as()?;
+                TagKind::Import(ImportTag { path, alias, trim, })
+            },
+            "data" => {
+                let (path, alias, trim) = self.path_as()?;
+                TagKind::Data(DataTag { path, alias, trim, })
+            },
+            "set" => {
+                let assignment = self.assignment()?;
+                TagKind::Set(SetTag { assignment, trim: self.trim_end(), })
+            },
+            "with" => {
+                let assignment = self.assignment()?;
+                TagKind::With { assignment, trim: self.trim_end(), }
+            },
+            "endwith" => TagKind::EndWith { trim, },
+            k => {
+                self.position = keyword_position;
+                return self.error(format!("unknown tag \"{k}\""));
+            },
+        };
+
+        if self.starts_with(TRIM_END) {
+            return self.error(format!("\"{keyword}\" cannot trim following content"));
+        }
+
+        self.expect(TAG[1])?;
+
+        Ok(Tag {
+            kind,
+            source: self.src[start..self.position].to_owned(),
+            position: start,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Scope {
+    Root,
+    If,
+    IfElse,
+    For,
+    ForElse,
+    Block,
+    Autoescape,
+    Macro,
+    With,
+}
+
+impl Scope {
+    fn is_closed_by(self, kind: &TagKind) -> bool {
+        matches!(
+            (self, kind),
+            (Self::If, TagKind::Else { .. }) |
+            (Self::If, TagKind::Elif { .. }) |
+            (Self::If, TagKind::EndIf { .. }) |
+            (Self::IfElse, TagKind::EndIf { .. }) |
+            (Self::For, TagKind::Else { .. }) |
+            (Self::For, TagKind::EndFor { .. }) |
+            (Self::ForElse, TagKind::EndFor { .. }) |
+            (Self::Block, TagKind::EndBlock { .. }) |
+            (Self::Autoescape, TagKind::EndAutoescape { .. }) |
+            (Self::Macro, TagKind::EndMacro { .. }) |
+            (Self::With, TagKind::EndWith { .. })
+        )
+    }
+}
+
+fn push_content(nodes: &mut Vec<Node>, s: &str) {
+    match nodes.last_mut() {
+        Some(Node::Content(c)) => c.push_str(s),
+        _ => nodes.push(Node::Content(s.to_owned())),
+    }
+}
+
+struct TreeBuilder<'a> {
+    lexer: Lexer<'a>,
+    /// Mistakes which are only errors in strict mode.
+    offences: Vec<Offence>,
+}
+
+impl TreeBuilder<'_> {
+    fn unclosed<T>(&self, tag: &Tag, keyword: &str) -> Result<T> {
+        Err(parse_error(
+            self.lexer.src,
+            tag.position,
+            tag.position,
+            format!("tag is never closed with \"{keyword}\""),
+        ))
+    }
+
+    fn nodes(&mut self, scope: Scope) -> Result<(Vec<Node>, Option<Tag>)> {
+        let mut nodes = Vec::new();
+
+        while let Some(token) = self.lexer.next_token()? {
+            match token {
+                Token::Content(c) => push_content(&mut nodes, &c),
+                Token::Variable(v) => nodes.push(Node::Variable(v)),
+                Token::Call(c) => nodes.push(Node::Call(c)),
+                Token::Tag(tag) => {
+                    if scope.is_closed_by(&tag.kind) {
+                        return Ok((nodes, Some(tag)));
+                    }
+
+                    self.tag(tag, &mut nodes)?;
+                },
+            }
+        }
+
+        Ok((nodes, None))
+    }
+
+    /// Parse the bodies of a tag which may contain an `else`.
+    fn bodies(
+        &mut self, tag: &Tag, trim: bool, keyword: &str, scope: Scope,
+        else_scope: Scope,
+    ) -> Result<(Body, Option<Body>)> {
+        let (then, end) = self.nodes(scope)?;
+
+        match end {
+            Some(Tag { kind: TagKind::Else { trim_before, trim_after, }, .. }) => {
+                let (otherwise, end) = self.nodes(else_scope)?;
+
+                match end {
+                    Some(Tag {
+                        kind: TagKind::EndIf { trim: trim_end, } | TagKind::EndFor { trim: trim_end, },
+                        ..
+                    }) => Ok((
+                        Body { nodes: then, trim_start: trim, trim_end: trim_before, },
+                        Some(Body { nodes: otherwise, trim_start: trim_after, trim_end, }),
+                    )),
+                    _ => self.unclosed(tag, keyword),
+                }
+            },
+            // an elif is an if within the else
+            Some(Tag { kind: TagKind::Elif { condition, trim_before, trim_after, }, .. }) => {
+                let (elif_then, elif_otherwise) = self.bodies(
+                    tag, trim_after, keyword, scope, else_scope
+                )?;
+
+                let elif = Node::If(IfTag {
+                    condition,
+                    then: elif_then,
+                    otherwise: elif_otherwise,
+                });
+
+                Ok((
+                    Body { nodes: then, trim_start: trim, trim_end: trim_before, },
+                    Some(Body { nodes: vec![ elif ], trim_start: false, trim_end: false, }),
+                ))
+            },
+            Some(Tag {
+                kind: TagKind::EndIf { trim: trim_end, } | TagKind::EndFor { trim: trim_end, },
+                ..
+            }) => Ok((Body { nodes: then, trim_start: trim, trim_end, }, None)),
+            _ => self.unclosed(tag, keyword),
+        }
+    }
+
+    fn tag(&mut self, tag: Tag, nodes: &mut Vec<Node>) -> Result<()> {
+        match &tag.kind {
+            TagKind::Include(include) => nodes.push(Node::Include(include.clone())),
+            TagKind::Import(import) => nodes.push(Node::Import(import.clone())),
+            TagKind::Data(data) => nodes.push(Node::Data(data.clone())),
+            TagKind::Call(call) => nodes.push(Node::Call(CallTag {
+                source: tag.source.clone(),
+                ..call.clone()
+            })),
+            TagKind::Set(set) => nodes.push(Node::Set(SetTag {
+                assignment: Assignment {
+                    source: tag.source.clone(),
+                    ..set.assignment.clone()
+                },
+                trim: set.trim,
+            })),
+            TagKind::If { condition, trim, } => {
+                let (then, otherwise) = self.bodies(
+                    &tag, *trim, "endif", Scope::If, Scope::IfElse
+                )?;
+
+                nodes.push(Node::If(IfTag {
+                    condition: condition.clone(),
+                    then,
+                    otherwise,
+                }));
+            },
+            TagKind::For { variable, iterable, sort, offset, limit, trim, } => {
+                let (body, otherwise) = self.bodies(
+                    &tag, *trim, "endfor", Scope::For, Scope::ForElse
+                )?;
+
+                nodes.push(Node::For(ForTag {
+                    variable: variable.to_owned(),
+                    iterable: iterable.clone(),
+                    sort: sort.clone(),
+                    offset: *offset,
+                    limit: *limit,
+                    body,
+                    otherwise,
+                }));
+            },
+            TagKind::Block { name, trim, } => {
+                let (body, end) = self.nodes(Scope::Block)?;
+
+                match end {
+                    Some(Tag { kind: TagKind::EndBlock { name: end_name, trim: trim_end, }, position, .. }) => {
+                        if let Some(end_name) = end_name.filter(|n| n != name) {
+                            self.offences.push(offence(
+                                self.lexer.src,
+                                position,
+                                position,
+                                format!("\"endblock {end_name}\" closes block \"{name}\""),
+                            ));
+                        }
+
+                        nodes.push(Node::Block(BlockTag {
+                            name: name.to_owned(),
+                            body: Body { nodes: body, trim_start: *trim, trim_end, },
+                        }));
+                    },
+                    _ => return self.unclosed(&tag, "endblock"),
+                }
+            },
+            TagKind::Autoescape { enabled, trim, } => {
+                let (body, end) = self.nodes(Scope::Autoescape)?;
+
+                match end {
+                    Some(Tag { kind: TagKind::EndAutoescape { trim: trim_end, }, .. }) => {
+                        nodes.push(Node::Autoescape(AutoescapeTag {
+                            enabled: *enabled,
+                            body: Body { nodes: body, trim_start: *trim, trim_end, },
+                        }));
+                    },
+                    _ => return self.unclosed(&tag, "endautoescape"),
+                }
+            },
+            TagKind::Macro { name, parameters, trim, } => {
+                let (body, end) = self.nodes(Scope::Macro)?;
+
+                match end {
+                    Some(Tag { kind: TagKind::EndMacro { trim: trim_end, }, .. }) => {
+                        nodes.push(Node::Macro(MacroTag {
+                            name: name.to_owned(),
+                            parameters: parameters.clone(),
+                            body: Body { nodes: body, trim_start: *trim, trim_end, },
+                        }));
+                    },
+                    _ => return self.unclosed(&tag, "endmacro"),
+                }
+            },
+            TagKind::With { assignment, trim, } => {
+                let (body, end) = self.nodes(Scope::With)?;
+
+                match end {
+                    Some(Tag { kind: TagKind::EndWith { trim: trim_end, }, .. }) => {
+                        nodes.push(Node::With(WithTag {
+                            assignment: Assignment {
+                                source: tag.source.clone(),
+                                ..assignment.clone()
+                            },
+                            body: Body { nodes: body, trim_start: *trim, trim_end, },
+                        }));
+                    },
+                    _ => return self.unclosed(&tag, "endwith"),
+                }
+            },
+            TagKind::Extends(_) | TagKind::Ignore => {
+                return Err(parse_error(
+                    self.lexer.src,
+                    tag.position,
+                    tag.position,
+                    "tag must be the first in the template",
+                ));
+            },
+            // closing tags which were never opened are content
+            _ => {
+                self.offences.push(offence(
+                    self.lexer.src,
+                    tag.position,
+                    tag.position,
+                    "tag closes nothing",
+                ));
+
+                push_content(nodes, &tag.source);
+            },
+        }
+
+        Ok(())
+    }
+}
+
+/// A parsed vg template which can be rendered any number of times.
+///
+/// # Examples
+///
+/// ```rust
+/// use vg_core::{ FileCache, Template, };
+///
+/// let mut cache = FileCache::enabled();
+/// let template = Template::parse("Hello, {{ name }}!", "/").unwrap();
+///
+/// let first = template.render_implemented("/", [("name", "World")], &mut cache)
+///     .unwrap();
+/// assert_eq!("Hello, World!", first);
+///
+/// let second = template.render_implemented("/", [("name", "Ron")], &mut cache)
+///     .unwrap();
+/// assert_eq!("Hello, Ron!", second);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    pub(crate) path: Option<PathBuf>,
+    directory: PathBuf,
+    ignored: bool,
+    extends: Option<PathValue>,
+    front_matter: BTreeMap<String, Value>,
+    nodes: Vec<Node>,
+    offences: Vec<Offence>,
+}
+
+impl Template {
+    /// Parse a template from source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The vg template source.
+    /// * `d` - The directory relative paths within the template are based on.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vg_core::{ Node, Template, };
+    ///
+    /// let template = Template::parse("<p>{{ text }}</p>", "/").unwrap();
+    /// assert_eq!(3, template.nodes().len());
+    /// assert!(matches!(template.nodes()[1], Node::Variable(_)));
+    /// ```
+    pub fn parse<S: AsRef<str>, D: AsRef<Path>>(source: S, d: D) -> Result<Self> {
+        let mut builder = TreeBuilder {
+            lexer: Lexer::new(source.as_ref()),
+            offences: Vec::new(),
+        };
+
+        let mut template = Self {
+            path: None,
+            directory: d.as_ref().into(),
+            ignored: false,
+            extends: None,
+            front_matter: BTreeMap::new(),
+            nodes: Vec::new(),
+            offences: Vec::new(),
+        };
+
+        // front matter is only recognized at the top, and ignore and extends
+        // only as the first tag after it
+        template.front_matter = builder.lexer.front_matter();
+        let first = builder.lexer.next_token()?;
+
+        let mut nodes = Vec::new();
+
+        match first {
+            Some(Token::Tag(Tag { kind: TagKind::Ignore, .. })) => {
+                template.ignored = true;
+                return Ok(template);
+            },
+            Some(Token::Tag(Tag { kind: TagKind::Extends(path), .. })) => {
+                template.extends = Some(path);
+            },
+            Some(Token::Content(c)) => push_content(&mut nodes, &c),
+            Some(Token::Variable(v)) => nodes.push(Node::Variable(v)),
+            Some(Token::Call(c)) => nodes.push(Node::Call(c)),
+            Some(Token::Tag(tag)) => builder.tag(tag, &mut nodes)?,
+            None => {},
+        }
+
+        let (rest, _) = builder.nodes(Scope::Root)?;
+
+        for node in rest {
+            match node {
+                Node::Content(c) => push_content(&mut nodes, &c),
+                node => nodes.push(node),
+            }
+        }
+
+        template.nodes = nodes;
+        template.offences = builder.offences;
+
+        Ok(template)
+    }
+
+    /// Retrieve a parsed template from file through the caching mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - The path to the vg template.
+    /// * `c` - The caching mechanism.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vg_core::{ FileCache, Template, };
+    ///
+    /// let mut cache = FileCache::enabled();
+    /// let template = Template::from_file("./test/full/2/page.jinja", &mut cache)
+    ///     .unwrap();
+    /// let output = template.render("./test/full/2", &mut cache).unwrap();
+    /// let against = include_str!("../test/full/2/against.jinja");
+    /// assert_eq!(against[0..against.len()-1], output);
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(p: P, c: &mut FileCache) -> Result<Arc<Self>> {
+        c.template(p)
+    }
+
+    /// The path of the file the template was read from.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The directory relative paths within the template are based on.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Whether the template begins with an `{% ignore %}` tag.
+    pub fn is_ignored(&self) -> bool {
+        self.ignored
+    }
+
+    /// The path given to the template's `{% extends %}` tag.
+    pub fn extends(&self) -> Option<&PathValue> {
+        self.extends.as_ref()
+    }
+
+    /// The values of the template's front matter, implemented before its
+    /// content is rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vg_core::{ Template, Value, };
+    ///
+    /// let template = Template::parse("---\ntitle: Hello\n---\n<h1>{{ title }}</h1>", "/")
+    ///     .unwrap();
+    /// assert_eq!(Some(&Value::from("Hello")), template.front_matter().get("title"));
+    /// ```
+    pub fn front_matter(&self) -> &BTreeMap<String, Value> {
+        &self.front_matter
+    }
+
+    /// The parsed nodes of the template.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Mistakes in the template which are only errors in strict mode, such as
+    /// an `endblock` naming a different block or an `endif` closing no tag.
+    pub fn offences(&self) -> &[Offence] {
+        &self.offences
+    }
+
+    /// Render the template.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - The path to the root directory.
+    /// * `c` - The caching mechanism.
+    pub fn render<R: AsRef<Path>>(&self, r: R, c: &mut FileCache) -> Result<String> {
+        self.render_implemented(r, std::iter::empty::<(&str, &str)>(), c)
+    }
+
+    /// Render the template with the given variable implementations.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - The path to the root directory.
+    /// * `i` - Implemented variables prior to rendering.
+    /// * `c` - The caching mechanism.
+    pub fn render_implemented<R, K, V, Impls>(&self, r: R, i: Impls, c: &mut FileCache) -> Result<String>
+    where
+        R: AsRef<Path>,
+        K: AsRef<str>,
+        V: Into<Value>,
+        Impls: IntoIterator<Item = (K, V)>
+    {
+        self.render_with_options(r, i, c, &CompileOptions::default())
+    }
+
+    /// Render the template with the given variable implementations and
+    /// options.
+    ///
+    /// # Arguments
+    ///
+    /// * `r` - The path to the root directory.
+    /// * `i` - Implemented variables prior to rendering.
+    /// * `c` - The caching mechanism.
+    /// * `o` - The compilation options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vg_core::{ CompileOptions, Error, FileCache, Template, };
+    ///
+    /// let mut cache = FileCache::enabled();
+    /// let template = Template::parse("{{ greeting }}, {{ name }}!", "/").unwrap();
+    /// let options = CompileOptions { strict: true, ..Default::default() };
+    ///
+    /// let output = template.render_with_options("/", [("name", "Ron")], &mut cache, &options);
+    /// assert!(matches!(output, Err(Error::Strict(offences)) if offences.len() == 1));
+    /// ```
+    pub fn render_with_options<R, K, V, Impls>(&self, r: R, i: Impls, c: &mut FileCache, o: &CompileOptions) -> Result<String>
+    where
+        R: AsRef<Path>,
+        K: AsRef<str>,
+        V: Into<Value>,
+        Impls: IntoIterator<Item = (K, V)>
+    {
+        let mut context = Context::default();
+
+        i.into_iter().for_each(|(k, v)| {
+            context.untrusted.insert(k.as_ref().to_owned());
+            context.implementations.insert(k.as_ref().to_owned(), v.into());
+        });
+
+        Renderer::new(r.as_ref(), c, o)?.render(self, &mut context)
+    }
+}
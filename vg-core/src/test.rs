@@ -16,7 +16,25 @@
 
 //! The primary tests behind the Very Good Templating Engine.
 
-use crate::{ FileCache, Parser, };
+use {
+    crate::{
+        CompileOptions,
+        Error,
+        FileCache,
+        Filter,
+        MemoryLoader,
+        OverlayLoader,
+        Parser,
+        Result,
+        Sandbox,
+        Template,
+        Value,
+    },
+    std::{
+        collections::BTreeMap,
+        path::PathBuf,
+    },
+};
 
 #[test]
 fn escape_1() {
@@ -294,6 +312,81 @@ fn if_12() {
     assert_eq!(&against[0..against.len()-1], output);
 }
 
+#[test]
+fn if_13() {
+    let output = Parser::compile(
+        "./test/if/13",
+        "./test/if/13/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/if/13/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn if_14() {
+    let impls = [
+        ("first", "Value"),
+        ("second", "Value"),
+        ("count", "12"),
+        ("name", "Twelve"),
+    ];
+
+    let output = Parser::compile_implemented(
+        "./test/if/14",
+        "./test/if/14/template.jinja",
+        impls
+    ).unwrap();
+
+    let against = include_str!("../test/if/14/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn if_15() {
+    let impls = [
+        ("title", "Title"),
+        ("order", "1"),
+        ("nothing", "Something"),
+    ];
+
+    let output = Parser::compile_implemented(
+        "./test/if/15",
+        "./test/if/15/template.jinja",
+        impls
+    ).unwrap();
+
+    let against = include_str!("../test/if/15/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn if_16() {
+    let output = Parser::compile(
+        "./test/if/16",
+        "./test/if/16/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/if/16/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn if_17() {
+    let output = Parser::compile(
+        "./test/if/17",
+        "./test/if/17/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/if/17/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
 #[test]
 fn include_1() {
     let output = Parser::compile(
@@ -469,6 +562,18 @@ fn include_14() {
     assert_eq!(against, output);
 }
 
+#[test]
+fn include_15() {
+    let output = Parser::compile(
+        "./test/include/15",
+        "./test/include/15/template.jinja",
+    ).unwrap();
+
+    let against = include_str!("../test/include/15/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
 #[test]
 fn for_1() {
     let output = Parser::compile(
@@ -787,3 +892,807 @@ fn variable_2() {
 
     assert_eq!(against, output);
 }
+
+#[test]
+fn template_1() {
+    let mut cache = FileCache::enabled();
+
+    let template = Template::from_file(
+        "./test/template/1/template.jinja",
+        &mut cache
+    ).unwrap();
+
+    let first = template.render_implemented(
+        "./test/template/1",
+        [ ("title", "First"), ("author", "Ron") ],
+        &mut cache
+    ).unwrap();
+
+    let second = template.render_implemented(
+        "./test/template/1",
+        [ ("title", "Second"), ("author", "Leslie") ],
+        &mut cache
+    ).unwrap();
+
+    let against_first = include_str!("../test/template/1/against_first.jinja");
+    let against_second = include_str!("../test/template/1/against_second.jinja");
+
+    assert_eq!(&against_first[0..against_first.len()-1], first);
+    assert_eq!(&against_second[0..against_second.len()-1], second);
+
+    // the layout was parsed once and reused
+    let layout = cache.info().into_iter()
+        .find(|(p, _)| p.ends_with("layout.jinja"))
+        .unwrap();
+
+    assert_eq!(1, layout.1);
+}
+
+#[test]
+fn parse_1() {
+    let output = Parser::compile(
+        "./test/parse/1",
+        "./test/parse/1/template.jinja"
+    );
+
+    match output {
+        Err(Error::Parse { path, line, column, snippet, .. }) => {
+            assert_eq!(Some(PathBuf::from("./test/parse/1/template.jinja")), path);
+            assert_eq!(2, line);
+            assert_eq!(10, column);
+            assert_eq!("{% for x \"dir\" %}", snippet);
+        },
+        o => panic!("Expected a parse error, got {o:?}"),
+    }
+}
+
+#[test]
+fn parse_2() {
+    let output = Parser::compile(
+        "./test/parse/2",
+        "./test/parse/2/template.jinja"
+    );
+
+    match output {
+        Err(Error::Parse { line, column, snippet, reason, .. }) => {
+            assert_eq!(3, line);
+            assert_eq!(14, column);
+            assert_eq!("{{ title | bold }}", snippet);
+            assert_eq!("unknown filter \"bold\"", reason);
+        },
+        o => panic!("Expected a parse error, got {o:?}"),
+    }
+}
+
+#[test]
+fn parse_3() {
+    let output = Parser::compile(
+        "./test/parse/3",
+        "./test/parse/3/template.jinja"
+    );
+
+    match output {
+        Err(Error::Parse { line, column, snippet, .. }) => {
+            assert_eq!(1, line);
+            assert_eq!(1, column);
+            assert_eq!("{% if title %}", snippet);
+        },
+        o => panic!("Expected a parse error, got {o:?}"),
+    }
+}
+
+#[test]
+fn parse_4() {
+    let output = Parser::compile(
+        "./test/parse/4",
+        "./test/parse/4/template.jinja"
+    );
+
+    match output {
+        Err(Error::Parse { line, column, .. }) => {
+            assert_eq!(2, line);
+            assert_eq!(1, column);
+        },
+        o => panic!("Expected a parse error, got {o:?}"),
+    }
+}
+
+#[test]
+fn strict_1() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { strict: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/strict/1",
+        "./test/strict/1/template.jinja",
+        std::iter::empty::<(&str, &str)>(),
+        &mut cache,
+        &options
+    );
+
+    let offences = match output {
+        Err(Error::Strict(offences)) => offences,
+        o => panic!("Expected strict offences, got {o:?}"),
+    };
+
+    let offences = offences.into_iter()
+        .map(|o| (o.path.unwrap(), o.line, o.column))
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![
+        (PathBuf::from("./test/strict/1/template.jinja"), 4, 1),
+        (PathBuf::from("./test/strict/1/template.jinja"), 3, 4),
+        (PathBuf::from("./test/strict/1/layout.jinja"), 5, 1),
+        (PathBuf::from("./test/strict/1/layout.jinja"), 2, 9),
+    ], offences);
+}
+
+#[test]
+fn strict_2() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { strict: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/strict/2",
+        "./test/strict/2/template.jinja",
+        [("title", "Hello")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    let against = include_str!("../test/strict/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn strict_3() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { strict: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/strict/3",
+        "./test/strict/3/template.jinja",
+        std::iter::empty::<(&str, &str)>(),
+        &mut cache,
+        &options
+    );
+
+    let offences = match output {
+        Err(Error::Strict(offences)) => offences,
+        o => panic!("Expected strict offences, got {o:?}"),
+    };
+
+    let offences = offences.into_iter()
+        .map(|o| (o.line, o.column, o.reason))
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec![
+        (1, 16, "unknown filter \"nope\"".to_owned()),
+        (2, 24, "unknown filter \"alsonope\"".to_owned()),
+        (1, 1, "\"a\" is not implemented".to_owned()),
+        (1, 9, "\"b\" is not implemented".to_owned()),
+        (1, 24, "\"c\" is not implemented".to_owned()),
+    ], offences);
+}
+
+#[test]
+fn loader_1() {
+    let loader = MemoryLoader::from_iter([
+        ("/page.jinja", "<ul>{% for post in \"/posts\" %}<li>{{ post }}</li>{% endfor %}</ul>"),
+        ("/posts/b.jinja", "Second"),
+        ("/posts/a.jinja", "First"),
+        ("/posts/drafts/c.jinja", "Draft"),
+    ]);
+
+    let upper = MemoryLoader::from_iter([
+        ("/posts/a.jinja", "Overridden"),
+    ]);
+
+    let mut cache = FileCache::enabled().with_loader(loader.clone());
+    let output = Parser::compile_with_cache("/", "/page.jinja", &mut cache).unwrap();
+    assert_eq!("<ul><li>First</li><li>Second</li></ul>", output);
+
+    let mut cache = FileCache::enabled().with_loader(OverlayLoader::new(upper, loader));
+    let output = Parser::compile_with_cache("/", "/page.jinja", &mut cache).unwrap();
+    assert_eq!("<ul><li>Overridden</li><li>Second</li></ul>", output);
+}
+
+#[cfg(unix)]
+#[test]
+fn loader_2() {
+    let root = std::env::temp_dir().join(format!("vg-loader-2-{}", std::process::id()));
+    let posts = root.join("posts");
+    std::fs::create_dir_all(&posts).unwrap();
+    std::fs::write(root.join("page.jinja"), "{% for post in \"./posts\" %}{{ post }}{% endfor %}").unwrap();
+    std::fs::write(posts.join("a.jinja"), "First").unwrap();
+    std::os::unix::fs::symlink(root.join("missing.jinja"), posts.join("broken.jinja")).unwrap();
+
+    // caches may be shared between threads
+    fn shared<T: Send + Sync>(_: &T) {}
+
+    let mut cache = FileCache::enabled();
+    shared(&cache);
+
+    let output = Parser::compile_with_cache(&root, root.join("page.jinja"), &mut cache);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!("First", output.unwrap());
+}
+
+#[test]
+fn sandbox_1() {
+    let mut cache = FileCache::enabled();
+    let mut options = CompileOptions { sandbox: Some(Sandbox::default()), ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/sandbox/1/root",
+        "./test/sandbox/1/root/page.jinja",
+        [("target", "../secret.jinja")],
+        &mut cache,
+        &options
+    );
+
+    assert!(matches!(output, Err(Error::PathOutsideRoot(_))));
+
+    options.sandbox = Some(Sandbox {
+        extra_roots: vec![ PathBuf::from("./test/sandbox/1") ],
+        ..Default::default()
+    });
+
+    let output = Parser::compile_with_options(
+        "./test/sandbox/1/root",
+        "./test/sandbox/1/root/page.jinja",
+        [("target", "../secret.jinja")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    assert_eq!("<p>Secret</p>", output);
+}
+
+#[test]
+fn sandbox_2() {
+    let mut cache = FileCache::enabled();
+    let mut options = CompileOptions { sandbox: Some(Sandbox::default()), ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/sandbox/2/root",
+        "./test/sandbox/2/root/page.jinja",
+        std::iter::empty::<(&str, &str)>(),
+        &mut cache,
+        &options
+    );
+
+    assert!(matches!(output, Err(Error::PathOutsideRoot(_))));
+
+    options.sandbox = Some(Sandbox { follow_symlinks: true, ..Default::default() });
+
+    let output = Parser::compile_with_options(
+        "./test/sandbox/2/root",
+        "./test/sandbox/2/root/page.jinja",
+        std::iter::empty::<(&str, &str)>(),
+        &mut cache,
+        &options
+    ).unwrap();
+
+    assert_eq!("<p>Secret</p>", output);
+}
+
+#[test]
+fn cycle_1() {
+    let output = Parser::compile(
+        "./test/cycle/1",
+        "./test/cycle/1/template.jinja"
+    );
+
+    match output {
+        Err(Error::Cycle(stack)) => assert_eq!(vec![
+            PathBuf::from("test/cycle/1/template.jinja"),
+            PathBuf::from("test/cycle/1/template.jinja"),
+        ], stack),
+        o => panic!("Expected a cycle, got {o:?}"),
+    }
+}
+
+#[test]
+fn cycle_2() {
+    let output = Parser::compile(
+        "./test/cycle/2",
+        "./test/cycle/2/template.jinja"
+    );
+
+    match output {
+        Err(Error::Cycle(stack)) => assert_eq!(vec![
+            PathBuf::from("test/cycle/2/template.jinja"),
+            PathBuf::from("test/cycle/2/layout.jinja"),
+            PathBuf::from("test/cycle/2/template.jinja"),
+        ], stack),
+        o => panic!("Expected a cycle, got {o:?}"),
+    }
+}
+
+#[test]
+fn nested_1() {
+    let output = Parser::compile(
+        "./test/nested/1",
+        "./test/nested/1/template.jinja"
+    );
+
+    match output {
+        Err(Error::Nested { stack, error, }) => {
+            assert_eq!(vec![
+                PathBuf::from("test/nested/1/template.jinja"),
+                PathBuf::from("test/nested/1/partials/card.jinja"),
+                PathBuf::from("test/nested/1/partials/missing.jinja"),
+            ], stack);
+            assert!(matches!(*error, Error::NotAFileError(_)));
+        },
+        o => panic!("Expected a nested error, got {o:?}"),
+    }
+}
+
+#[test]
+fn nested_2() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { max_depth: 2, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/nested/1",
+        "./test/nested/1/template.jinja",
+        std::iter::empty::<(&str, &str)>(),
+        &mut cache,
+        &options
+    );
+
+    match output {
+        Err(Error::MaxDepth(stack)) => assert_eq!(3, stack.len()),
+        o => panic!("Expected the maximum depth to be exceeded, got {o:?}"),
+    }
+}
+
+#[test]
+fn autoescape_1() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { autoescape: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/autoescape/1",
+        "./test/autoescape/1/template.jinja",
+        [("comment", "<b>\"Tom\" & 'Jerry'</b>")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    let against = include_str!("../test/autoescape/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+/// Wraps a value between two arguments, or on both sides by one argument.
+#[derive(Debug)]
+struct Wrap;
+
+impl Filter for Wrap {
+    fn name(&self) -> &str {
+        "wrap"
+    }
+
+    fn apply(&self, input: &str, arguments: &[String]) -> Result<String> {
+        match arguments {
+            [both] => Ok(format!("{both}{input}{both}")),
+            [start, end] => Ok(format!("{start}{input}{end}")),
+            _ => Err(Error::Filter {
+                name: "wrap".to_owned(),
+                reason: "expected 1 or 2 arguments".to_owned(),
+            }),
+        }
+    }
+}
+
+#[test]
+fn filter_1() {
+    let mut cache = FileCache::enabled();
+    let mut options = CompileOptions::default();
+    options.filters.insert(Wrap);
+
+    let output = Parser::compile_with_options(
+        "./test/filter/1",
+        "./test/filter/1/template.jinja",
+        [("name", "Frankie Baffa")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    let against = include_str!("../test/filter/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn filter_2() {
+    let mut cache = FileCache::enabled();
+    cache.insert("/page.jinja", "{{ name | trim \"extra\" }}".to_owned());
+
+    let output = Parser::compile_with_options(
+        "/", "/page.jinja", [("name", "Frankie")], &mut cache, &CompileOptions::default()
+    );
+
+    match output {
+        Err(Error::Filter { name, .. }) => assert_eq!("trim", name),
+        o => panic!("Expected a filter error, got {o:?}"),
+    }
+}
+
+/// A filter replacing `timeago`, which counts the arguments it is given.
+#[derive(Debug)]
+struct Arguments;
+
+impl Filter for Arguments {
+    fn name(&self) -> &str {
+        "timeago"
+    }
+
+    fn apply(&self, _: &str, arguments: &[String]) -> Result<String> {
+        Ok(arguments.len().to_string())
+    }
+}
+
+#[test]
+fn filter_3() {
+    let mut cache = FileCache::enabled();
+    let mut options = CompileOptions::default();
+    options.filters.insert(Arguments);
+    cache.insert("/page.jinja", "{{ date | timeago }}".to_owned());
+
+    let output = Parser::compile_with_options(
+        "/", "/page.jinja", [("date", "2020-01-01")], &mut cache, &options
+    ).unwrap();
+
+    assert_eq!("0", output);
+}
+
+#[test]
+fn default_1() {
+    let output = Parser::compile_implemented(
+        "./test/default/1",
+        "./test/default/1/template.jinja",
+        [("title", "Home")]
+    ).unwrap();
+
+    let against = include_str!("../test/default/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn text_1() {
+    let output = Parser::compile_implemented(
+        "./test/text/1",
+        "./test/text/1/template.jinja",
+        [
+            ("title", "a VERY good post!"),
+            ("body", "<p>The first paragraph.</p>\n<p>The <em>second</em> one.</p>"),
+        ]
+    ).unwrap();
+
+    let against = include_str!("../test/text/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn encoding_1() {
+    let output = Parser::compile_implemented(
+        "./test/encoding/1",
+        "./test/encoding/1/template.jinja",
+        [("query", "Tom & Jerry's \"show\"")]
+    ).unwrap();
+
+    let against = include_str!("../test/encoding/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn encoding_2() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { autoescape: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/encoding/2",
+        "./test/encoding/2/template.jinja",
+        [("name", "<b>\"Tom\" & 'Jerry'</b>")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    let against = include_str!("../test/encoding/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn macro_1() {
+    let output = Parser::compile_implemented(
+        "./test/macro/1",
+        "./test/macro/1/template.jinja",
+        [("page.title", "First Post"), ("page.url", "/posts/1.html")]
+    ).unwrap();
+
+    let against = include_str!("../test/macro/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn macro_2() {
+    let output = Parser::compile(
+        "./test/macro/2",
+        "./test/macro/2/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/macro/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn macro_3() {
+    let mut cache = FileCache::enabled();
+    let options = CompileOptions { strict: true, ..Default::default() };
+
+    let output = Parser::compile_with_options(
+        "./test/macro/3",
+        "./test/macro/3/template.jinja",
+        [("site", "Example")],
+        &mut cache,
+        &options
+    ).unwrap();
+
+    let against = include_str!("../test/macro/3/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn macro_4() {
+    let output = Parser::compile_implemented(
+        "./test/macro/4",
+        "./test/macro/4/template.jinja",
+        [("site", "Example")]
+    ).unwrap();
+
+    let against = include_str!("../test/macro/4/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn set_1() {
+    let output = Parser::compile_implemented(
+        "./test/set/1",
+        "./test/set/1/template.jinja",
+        [("page.title", "Home")]
+    ).unwrap();
+
+    let against = include_str!("../test/set/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn value_1() {
+    let page = Value::Map(BTreeMap::from([
+        ("title".to_owned(), Value::from("Home")),
+        ("tags".to_owned(), Value::from(vec![ "rust", "web" ])),
+    ]));
+
+    let output = Parser::compile_implemented(
+        "./test/value/1",
+        "./test/value/1/template.jinja",
+        [("page", page), ("count", Value::from(2))]
+    ).unwrap();
+
+    let against = include_str!("../test/value/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn value_2() {
+    let posts = Value::from(vec![
+        BTreeMap::from([("title", "First")]),
+        BTreeMap::from([("title", "<b>Second</b>")]),
+    ]);
+
+    let mut cache = FileCache::enabled();
+    cache.insert(
+        "/page.jinja",
+        "{% for post in posts | !name %}{{ post.title }};{% endfor %}".to_owned()
+    );
+
+    let options = CompileOptions { autoescape: true, ..Default::default() };
+    let output = Parser::compile_with_options(
+        "/", "/page.jinja", [("posts", posts)], &mut cache, &options
+    ).unwrap();
+
+    assert_eq!("&lt;b&gt;Second&lt;/b&gt;;First;", output);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_1() {
+    #[derive(serde::Serialize)]
+    struct Link {
+        label: &'static str,
+        href: &'static str,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Site {
+        name: &'static str,
+        year: u32,
+        links: Vec<Link>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct Page {
+        title: &'static str,
+        subtitle: Option<&'static str>,
+        draft: bool,
+        site: Site,
+    }
+
+    let page = Page {
+        title: "Home",
+        subtitle: None,
+        draft: true,
+        site: Site {
+            name: "Very Good",
+            year: 2024,
+            links: vec![
+                Link { label: "Home", href: "/", },
+                Link { label: "About", href: "/about.html", },
+            ],
+        },
+    };
+
+    let output = Parser::compile_serialize(
+        "./test/serialize/1",
+        "./test/serialize/1/template.jinja",
+        &page
+    ).unwrap();
+
+    let against = include_str!("../test/serialize/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn data_1() {
+    let output = Parser::compile(
+        "./test/data/1",
+        "./test/data/1/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/data/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn data_2() {
+    let output = Parser::compile(
+        "./test/data/2",
+        "./test/data/2/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/data/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn front_1() {
+    let output = Parser::compile(
+        "./test/front/1",
+        "./test/front/1/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/front/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn front_2() {
+    let output = Parser::compile(
+        "./test/front/2",
+        "./test/front/2/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/front/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn front_3() {
+    let output = Parser::compile(
+        "./test/front/3",
+        "./test/front/3/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/front/3/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn file_1() {
+    let output = Parser::compile(
+        "./test/file/1",
+        "./test/file/1/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/file/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn file_2() {
+    let output = Parser::compile(
+        "./test/file/2",
+        "./test/file/2/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/file/2/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn date_1() {
+    let impls = [
+        ("published", "2024-03-05T10:30:00Z"),
+        ("stamp", "1700000000"),
+        // 2025-01-01T00:00:00Z
+        ("now", "1735689600"),
+    ];
+
+    let output = Parser::compile_implemented(
+        "./test/date/1",
+        "./test/date/1/template.jinja",
+        impls
+    ).unwrap();
+
+    let against = include_str!("../test/date/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn sort_1() {
+    let output = Parser::compile(
+        "./test/sort/1",
+        "./test/sort/1/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/sort/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
+
+#[test]
+fn limit_1() {
+    let output = Parser::compile(
+        "./test/limit/1",
+        "./test/limit/1/template.jinja"
+    ).unwrap();
+
+    let against = include_str!("../test/limit/1/against.jinja");
+
+    assert_eq!(&against[0..against.len()-1], output);
+}
@@ -0,0 +1,222 @@
+// vg-core::value: The values implementing vg variables.
+// Copyright (C) 2024  Frankie Baffa
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The values implementing vg variables.
+
+use std::{
+    collections::{ BTreeMap, HashMap, },
+    fmt::{ Display, Formatter, Result as FmtResult, },
+};
+
+#[cfg(feature = "serde")]
+use crate::{ Error, Result, data::from_json, };
+
+/// The value implementing a variable. Strings convert into values, so
+/// string-only implementations keep working.
+///
+/// # Examples
+///
+/// ```rust
+/// use {
+///     vg_core::{ Parser, Value, },
+///     std::collections::BTreeMap,
+/// };
+///
+/// let page = Value::Map(BTreeMap::from([
+///     ("title".to_owned(), Value::from("Home")),
+///     ("tags".to_owned(), Value::from(vec![ "rust", "web" ])),
+/// ]));
+///
+/// let output = Parser::compile_implemented(
+///     "./test/value/1",
+///     "./test/value/1/template.jinja",
+///     [("page", page), ("count", Value::from(2))]
+/// ).unwrap();
+///
+/// let against = include_str!("../test/value/1/against.jinja");
+/// assert_eq!(&against[0..against.len()-1], output);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Value {
+    /// Nothing, which is never implemented.
+    #[default]
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Resolve a dotted path within the value. Maps are indexed by key and
+    /// lists by position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vg_core::Value;
+    ///
+    /// let tags = Value::from(vec![ "rust", "web" ]);
+    /// assert_eq!(Some(&Value::from("web")), tags.get("1"));
+    /// assert_eq!(None, tags.get("2"));
+    /// ```
+    pub fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.').try_fold(self, |value, key| match value {
+            Self::Map(map) => map.get(key),
+            Self::List(list) => key.parse::<usize>().ok().and_then(|i| list.get(i)),
+            _ => None,
+        })
+    }
+
+    /// Whether the value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Whether the value is null, an empty string or an empty collection.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Null => true,
+            Self::String(s) => s.is_empty(),
+            Self::List(l) => l.is_empty(),
+            Self::Map(m) => m.is_empty(),
+            Self::Bool(_) | Self::Number(_) => false,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Value {
+    /// Convert a serializable value, such as a struct, into a value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use {
+    ///     vg_core::Value,
+    ///     std::collections::BTreeMap,
+    /// };
+    ///
+    /// let site = BTreeMap::from([ ("pages", vec![ 1, 2 ]) ]);
+    /// let value = Value::from_serialize(&site).unwrap();
+    /// assert_eq!(Some(&Value::from(2)), value.get("pages.1"));
+    /// ```
+    pub fn from_serialize<S: serde::Serialize + ?Sized>(s: &S) -> Result<Self> {
+        serde_json::to_value(s)
+            .map(from_json)
+            .map_err(|e| Error::Serialize(e.to_string()))
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, fmtr: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Null => Ok(()),
+            Self::Bool(b) => b.fmt(fmtr),
+            // whole numbers are written without a fraction
+            Self::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => {
+                fmtr.write_fmt(format_args!("{}", *n as i64))
+            },
+            Self::Number(n) => n.fmt(fmtr),
+            Self::String(s) => fmtr.write_str(s),
+            Self::List(l) => {
+                for (idx, value) in l.iter().enumerate() {
+                    if idx > 0 {
+                        fmtr.write_str(", ")?;
+                    }
+
+                    value.fmt(fmtr)?;
+                }
+
+                Ok(())
+            },
+            Self::Map(m) => {
+                for (idx, (key, value)) in m.iter().enumerate() {
+                    if idx > 0 {
+                        fmtr.write_str(", ")?;
+                    }
+
+                    fmtr.write_fmt(format_args!("{key}: {value}"))?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(s: &String) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+macro_rules! from_number {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Value {
+                fn from(n: $ty) -> Self {
+                    Self::Number(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+from_number!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<V: Into<Value>> From<Option<V>> for Value {
+    fn from(o: Option<V>) -> Self {
+        o.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<V: Into<Value>> From<Vec<V>> for Value {
+    fn from(v: Vec<V>) -> Self {
+        Self::List(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(m: BTreeMap<K, V>) -> Self {
+        Self::Map(m.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<HashMap<K, V>> for Value {
+    fn from(m: HashMap<K, V>) -> Self {
+        Self::Map(m.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+}
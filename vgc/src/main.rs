@@ -18,10 +18,10 @@
 
 use {
     args::{ Arguments, OptionType, },
-    vg_core::{ FileCache, Parser, Error, Result, },
+    vg_core::{ CompileOptions, FileCache, Parser, Error, Result, },
     std::{
         collections::HashMap,
-        io::{ Error as IOError, ErrorKind, },
+        io::Error as IOError,
         path::PathBuf,
     },
 };
@@ -33,6 +33,8 @@ const LICENSE_FULL: &str = include_str!("../../LICENSE-GPL");
 #[derive(Default)]
 struct Options {
     no_cache: bool,
+    strict: bool,
+    autoescape: bool,
     implementations: Vec<String>,
     cached_items: Vec<String>,
     root: Option<PathBuf>,
@@ -49,6 +51,8 @@ fn main() -> Result<()> {
                     std::process::exit(0);
                 },
                 "n"|"no-cache" => opts.no_cache = true,
+                "s"|"strict" => opts.strict = true,
+                "a"|"autoescape" => opts.autoescape = true,
                 "i"|"implementation" => {
                     opts.implementations.push(args.enforce_next_value(&arg)?);
                 },
@@ -64,8 +68,7 @@ fn main() -> Result<()> {
                     std::process::exit(0);
                 },
                 c => {
-                    return Err(IOError::new(
-                        ErrorKind::Other,
+                    return Err(IOError::other(
                         format!("{c} is not a valid argument."),
                     ));
                 },
@@ -77,27 +80,20 @@ fn main() -> Result<()> {
             },
         }
         Ok(())
-    }).map_err(|e| Error::IOError(e))?;
+    }).map_err(Error::IOError)?;
 
     let Options {
-        root, target, implementations, no_cache, cached_items
+        root, target, implementations, no_cache, strict, autoescape,
+        cached_items
     } = opts;
 
-    let root = root.map_or(
-        Err(Error::IOError(IOError::new(
-            ErrorKind::Other,
-            "-r|--root must be defined.".to_owned(),
-        ))),
-        |v| Ok(v)
-    )?;
-
-    let target = target.map_or(
-        Err(Error::IOError(IOError::new(
-            ErrorKind::Other,
-            "-r|--root must be defined.".to_owned(),
-        ))),
-        |v| Ok(v)
-    )?;
+    let root = root.ok_or(Error::IOError(IOError::other(
+        "-r|--root must be defined.".to_owned(),
+    )))?;
+
+    let target = target.ok_or(Error::IOError(IOError::other(
+        "-r|--root must be defined.".to_owned(),
+    )))?;
 
     let implementations = implementations.into_iter()
         .map(|i| {
@@ -108,6 +104,8 @@ fn main() -> Result<()> {
         })
         .collect::<HashMap<String, String>>();
 
+    let options = CompileOptions { strict, autoescape, ..Default::default() };
+
     let output = if !no_cache {
         let mut cache = FileCache::enabled();
 
@@ -125,9 +123,10 @@ fn main() -> Result<()> {
             cache.insert(path, v.to_owned());
         });
 
-        Parser::compile_implemented_with_cache(&root, &target, implementations, &mut cache)?
+        Parser::compile_with_options(&root, &target, implementations, &mut cache, &options)?
     } else {
-        Parser::compile_implemented(root, target, implementations)?
+        let mut cache = FileCache::disabled();
+        Parser::compile_with_options(root, target, implementations, &mut cache, &options)?
     };
 
     println!("{output}");
@@ -23,11 +23,11 @@ use {
         collections::HashMap,
         env,
         fs::{ OpenOptions, create_dir_all, copy, remove_file, },
-        io::{ Error as IOError, ErrorKind, Read, Write, },
+        io::{ Error as IOError, Read, Write, },
         path::PathBuf,
         time::{ Duration, Instant, },
     },
-    vg_core::{ Error, FileCache, Parser, Result, },
+    vg_core::{ CompileOptions, Error, FileCache, Parser, Result, },
 };
 
 const HELP: &str = include_str!("../resources/help.txt");
@@ -104,12 +104,18 @@ enum Action {
 #[derive(Clone, Deserialize, Serialize)]
 struct Actions {
     root: PathBuf,
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    autoescape: bool,
     actions: Vec<Action>
 }
 
 fn example_config() -> Actions {
     Actions {
         root: "path/to/root/dir".into(),
+        strict: false,
+        autoescape: false,
         actions: vec![
             Action::CompileFile(CompileFileOptions {
                 source: "path/to.source".into(),
@@ -228,8 +234,7 @@ fn main() -> Result<()> {
                     std::process::exit(0);
                 },
                 c => {
-                    return Err(IOError::new(
-                        ErrorKind::Other,
+                    return Err(IOError::other(
                         format!("Invalid argument {}", c),
                     ));
                 },
@@ -237,14 +242,13 @@ fn main() -> Result<()> {
             OptionType::Value(_) => if arg.is_last() {
                 opts.config = Some(PathBuf::from(arg.qualifier()));
             } else {
-                return Err(IOError::new(
-                    ErrorKind::Other,
+                return Err(IOError::other(
                     "Value in illegal position.".to_owned(),
                 ));
             },
         }
         Ok(())
-    }).map_err(|e| Error::IOError(e))?;
+    }).map_err(Error::IOError)?;
 
     let Options {
         example_config, config, read_only, timing, verbose, implementations,
@@ -287,6 +291,8 @@ fn main() -> Result<()> {
     if example_config {
         let cfg = Actions {
             root: "path/to/root/dir".into(),
+            strict: false,
+            autoescape: false,
             actions: vec![
                 Action::CompileFile(CompileFileOptions {
                     source: "path/to.source".into(),
@@ -354,6 +360,8 @@ fn main() -> Result<()> {
 
     let root = config.root;
 
+    let options = CompileOptions { strict: config.strict, ..Default::default() };
+
     if read_only {
         return Ok(());
     }
@@ -402,8 +410,14 @@ fn main() -> Result<()> {
                     });
 
                     let start = Instant::now();
-                    let source_res = Parser::compile_implemented_with_cache(
-                        root.clone(), &opts.source, global, &mut cache
+                    let options = CompileOptions {
+                        autoescape: config.autoescape &&
+                            CompileOptions::autoescapes(&opts.destination),
+                        ..options.clone()
+                    };
+
+                    let source_res = Parser::compile_with_options(
+                        root.clone(), &opts.source, global, &mut cache, &options
                     );
 
                     let source = match source_res {
@@ -509,9 +523,15 @@ fn main() -> Result<()> {
                         dest.push(filename);
                         dest = dest.with_extension(&destination.extension);
 
+                        let options = CompileOptions {
+                            autoescape: config.autoescape &&
+                                CompileOptions::autoescapes(&dest),
+                            ..options.clone()
+                        };
+
                         let start = Instant::now();
-                        let source_res = Parser::compile_implemented_with_cache(
-                            root.clone(), &path, global, &mut cache
+                        let source_res = Parser::compile_with_options(
+                            root.clone(), &path, global, &mut cache, &options
                         );
 
                         let source = match source_res {
@@ -622,10 +642,9 @@ fn main() -> Result<()> {
         println!("{total}s");
     }
 
-    if cache_info && cache_details.is_some() && !no_cache {
+    if let Some(details) = cache_details.filter(|_| cache_info && !no_cache) {
         let pre = if did_bench { "\n" } else { "" };
 
-        let details = cache_details.unwrap();
         let padding = details.iter()
             .map(|d| format!("{d:?}"))
             .map(|d| d.len())